@@ -0,0 +1,134 @@
+use std::fmt::Display;
+use std::hint::black_box;
+use std::time::{Duration, Instant};
+
+use serde::Serialize;
+
+use crate::error::ParseError;
+use crate::solution::Solver;
+
+/// Summary statistics for one phase of a day, in nanoseconds
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+pub struct Summary {
+    pub min_ns: u64,
+    pub median_ns: u64,
+    pub p95_ns: u64,
+}
+
+impl Summary {
+    pub fn from_samples(samples: &[Duration]) -> Self {
+        let mut nanos: Vec<u64> = samples
+            .iter()
+            .map(|sample| u64::try_from(sample.as_nanos()).unwrap_or(u64::MAX))
+            .collect();
+        nanos.sort_unstable();
+
+        Self {
+            min_ns: nanos[0],
+            median_ns: percentile(&nanos, 50),
+            p95_ns: percentile(&nanos, 95),
+        }
+    }
+}
+
+impl Display for Summary {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "min {:>12?}  median {:>12?}  p95 {:>12?}",
+            Duration::from_nanos(self.min_ns),
+            Duration::from_nanos(self.median_ns),
+            Duration::from_nanos(self.p95_ns)
+        )
+    }
+}
+
+/// Nearest-rank percentile of already sorted, non-empty samples
+fn percentile(sorted: &[u64], percent: usize) -> u64 {
+    let rank = (sorted.len() * percent).div_ceil(100);
+    sorted[rank.max(1) - 1]
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct DayBench {
+    pub day: u32,
+    pub iterations: usize,
+    pub parse: Summary,
+    pub part1: Summary,
+    pub part2: Summary,
+}
+
+impl Display for DayBench {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        writeln!(f, "Day {} ({} iterations)", self.day, self.iterations)?;
+        writeln!(f, "\tparse:  {}", self.parse)?;
+        writeln!(f, "\tpart 1: {}", self.part1)?;
+        write!(f, "\tpart 2: {}", self.part2)
+    }
+}
+
+fn time<T>(f: impl FnOnce() -> T) -> (T, Duration) {
+    let start = Instant::now();
+    let result = black_box(f());
+    (result, start.elapsed())
+}
+
+/// Parses and solves both parts `iterations` times, timing each phase separately
+pub fn bench_day(
+    day: u32,
+    solver: &dyn Solver,
+    input: &str,
+    iterations: usize,
+) -> Result<DayBench, ParseError> {
+    let mut parse_samples = Vec::with_capacity(iterations);
+    let mut part1_samples = Vec::with_capacity(iterations);
+    let mut part2_samples = Vec::with_capacity(iterations);
+
+    for _ in 0..iterations.max(1) {
+        let (parsed, parse_time) = time(|| solver.parse(black_box(input)));
+        let parsed = parsed?;
+        let (_, part1_time) = time(|| parsed.part1());
+        let (_, part2_time) = time(|| parsed.part2());
+
+        parse_samples.push(parse_time);
+        part1_samples.push(part1_time);
+        part2_samples.push(part2_time);
+    }
+
+    Ok(DayBench {
+        day,
+        iterations: parse_samples.len(),
+        parse: Summary::from_samples(&parse_samples),
+        part1: Summary::from_samples(&part1_samples),
+        part2: Summary::from_samples(&part2_samples),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::solution::registry;
+
+    #[test]
+    fn summary_test() {
+        let samples: Vec<Duration> = (1..=20).rev().map(Duration::from_nanos).collect();
+        let summary = Summary::from_samples(&samples);
+        assert_eq!(
+            summary,
+            Summary {
+                min_ns: 1,
+                median_ns: 10,
+                p95_ns: 19,
+            }
+        );
+    }
+
+    #[test]
+    fn bench_day_test() {
+        let registry = registry();
+        let result = bench_day(1, registry[&1].as_ref(), "1000\n2000\n\n4000", 3).unwrap();
+        assert_eq!(result.day, 1);
+        assert_eq!(result.iterations, 3);
+        assert!(result.parse.min_ns <= result.parse.p95_ns);
+    }
+}
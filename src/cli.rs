@@ -0,0 +1,672 @@
+use std::fmt::Display;
+use std::fs;
+use std::io::{self, Read};
+use std::path::{Path, PathBuf};
+
+use crate::bench::{bench_day, DayBench};
+use crate::day12::{render_distance_field, ClimbingRules, Map, Selector};
+use crate::scaffold::new_day;
+use crate::solution::{registry, Part};
+use crate::verify::{error_checks, parse_answers, verify_day, Check, Outcome};
+
+pub const USAGE: &str = "Usage:
+    advent-of-code-2022 run --all
+    advent-of-code-2022 run --day <n> [--part <1|2>] [--input <path|->]
+    advent-of-code-2022 bench (--all | --day <n>) [--iterations <n>] [--json]
+    advent-of-code-2022 verify [--day <n>] [--answers <path>]
+    advent-of-code-2022 new-day <n>
+    advent-of-code-2022 climb --from <cell>... [--to <cell>...] [--field] [--unreachable] [--input <path|->]
+
+Options:
+    -d, --day <n>           Run a single day
+    -a, --all               Run every implemented day
+    -p, --part <1|2>        Only run one part
+    -i, --input <path>      Read puzzle input from <path> instead of input/2022/day<n>.txt,
+                            or from stdin if <path> is -
+    -n, --iterations <n>    Number of times to run each day when benchmarking (default 10)
+        --json              Print benchmark results as JSON
+        --answers <path>    Answers to verify against (default answers/2022.txt)
+        --from <cell>       Where a day 12 climb can start: S, E, a height from a to z, or x,y.
+                            Can be given more than once
+        --to <cell>         Where a day 12 climb can finish, given the same way as --from
+        --field             Print the distance to every cell of the day 12 map
+        --unreachable       List the day 12 cells that can't be reached";
+
+const DEFAULT_ITERATIONS: usize = 10;
+const DEFAULT_ANSWERS: &str = "answers/2022.txt";
+
+#[derive(Debug, PartialEq, Eq)]
+pub enum Days {
+    All,
+    One(u32),
+}
+
+#[derive(Debug, PartialEq, Eq)]
+pub enum InputSource {
+    File(PathBuf),
+    Stdin,
+}
+
+impl InputSource {
+    pub fn default_for(day: u32) -> Self {
+        Self::File(PathBuf::from(format!("input/2022/day{day}.txt")))
+    }
+
+    /// Reads the whole input, trimming trailing newlines the same way aoc-runner does
+    pub fn read(&self) -> io::Result<String> {
+        let mut input = match self {
+            InputSource::File(path) => fs::read_to_string(path)?,
+            InputSource::Stdin => {
+                let mut buffer = String::new();
+                io::stdin().read_to_string(&mut buffer)?;
+                buffer
+            }
+        };
+
+        let trimmed_length = input.trim_end_matches('\n').len();
+        input.truncate(trimmed_length);
+        Ok(input)
+    }
+}
+
+impl Display for InputSource {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            InputSource::File(path) => write!(f, "{}", path.display()),
+            InputSource::Stdin => write!(f, "<stdin>"),
+        }
+    }
+}
+
+#[derive(Debug, PartialEq, Eq)]
+pub enum Command {
+    Run {
+        days: Days,
+        part: Option<Part>,
+        input: Option<InputSource>,
+    },
+    Bench {
+        days: Days,
+        iterations: usize,
+        json: bool,
+    },
+    Verify {
+        days: Days,
+        answers: PathBuf,
+    },
+    NewDay {
+        day: u32,
+    },
+    /// Queries the day 12 heightmap
+    Climb {
+        from: Vec<Selector>,
+        to: Vec<Selector>,
+        field: bool,
+        unreachable: bool,
+        input: Option<InputSource>,
+    },
+    Help,
+}
+
+#[derive(Debug, PartialEq, Eq)]
+pub struct UsageError(pub String);
+
+impl Display for UsageError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.0)
+    }
+}
+
+impl std::error::Error for UsageError {}
+
+fn usage_error<T>(message: impl Into<String>) -> Result<T, UsageError> {
+    Err(UsageError(message.into()))
+}
+
+fn flag_value(flag: &str, value: Option<String>) -> Result<String, UsageError> {
+    value.ok_or_else(|| UsageError(format!("{flag} needs a value")))
+}
+
+fn parse_day(value: &str) -> Result<u32, UsageError> {
+    match value.parse() {
+        Ok(day) if registry().contains_key(&day) => Ok(day),
+        Ok(day) => usage_error(format!("day {day} is not implemented")),
+        Err(_) => usage_error(format!("invalid day '{value}'")),
+    }
+}
+
+fn parse_part(value: &str) -> Result<Part, UsageError> {
+    match value {
+        "1" => Ok(Part::One),
+        "2" => Ok(Part::Two),
+        _ => usage_error(format!("invalid part '{value}', expected 1 or 2")),
+    }
+}
+
+/// Every flag any subcommand accepts. Each subcommand rejects the ones it doesn't use.
+#[derive(Default)]
+struct Flags {
+    all: bool,
+    day: Option<u32>,
+    part: Option<Part>,
+    input: Option<InputSource>,
+    iterations: Option<usize>,
+    json: bool,
+    answers: Option<PathBuf>,
+}
+
+impl Flags {
+    fn parse(mut args: impl Iterator<Item = String>) -> Result<Self, UsageError> {
+        let mut flags = Flags::default();
+
+        while let Some(arg) = args.next() {
+            match arg.as_str() {
+                "-a" | "--all" => flags.all = true,
+                "-d" | "--day" => flags.day = Some(parse_day(&flag_value(&arg, args.next())?)?),
+                "-p" | "--part" => flags.part = Some(parse_part(&flag_value(&arg, args.next())?)?),
+                "-i" | "--input" => {
+                    flags.input = Some(match flag_value(&arg, args.next())?.as_str() {
+                        "-" => InputSource::Stdin,
+                        path => InputSource::File(PathBuf::from(path)),
+                    })
+                }
+                "-n" | "--iterations" => {
+                    let value = flag_value(&arg, args.next())?;
+                    match value.parse() {
+                        Ok(iterations) if iterations > 0 => flags.iterations = Some(iterations),
+                        _ => return usage_error(format!("invalid iteration count '{value}'")),
+                    }
+                }
+                "--json" => flags.json = true,
+                "--answers" => flags.answers = Some(PathBuf::from(flag_value(&arg, args.next())?)),
+                other => return usage_error(format!("unexpected argument '{other}'")),
+            }
+        }
+
+        Ok(flags)
+    }
+
+    fn days(&self, command: &str) -> Result<Days, UsageError> {
+        match (self.all, self.day) {
+            (true, None) => Ok(Days::All),
+            (false, Some(day)) => Ok(Days::One(day)),
+            (true, Some(_)) => usage_error("--all and --day can't be used together"),
+            (false, None) => usage_error(format!("{command} needs either --all or --day <n>")),
+        }
+    }
+}
+
+fn parse_run_args(args: impl Iterator<Item = String>) -> Result<Command, UsageError> {
+    let flags = Flags::parse(args)?;
+    let days = flags.days("run")?;
+
+    if flags.iterations.is_some() || flags.json {
+        return usage_error("--iterations and --json can only be used with bench");
+    }
+    if flags.answers.is_some() {
+        return usage_error("--answers can only be used with verify");
+    }
+    if days == Days::All && flags.input.is_some() {
+        return usage_error("--input can only be used with a single --day");
+    }
+
+    Ok(Command::Run {
+        days,
+        part: flags.part,
+        input: flags.input,
+    })
+}
+
+fn parse_bench_args(args: impl Iterator<Item = String>) -> Result<Command, UsageError> {
+    let flags = Flags::parse(args)?;
+    let days = flags.days("bench")?;
+
+    if flags.part.is_some() || flags.input.is_some() {
+        return usage_error("--part and --input can only be used with run");
+    }
+    if flags.answers.is_some() {
+        return usage_error("--answers can only be used with verify");
+    }
+
+    Ok(Command::Bench {
+        days,
+        iterations: flags.iterations.unwrap_or(DEFAULT_ITERATIONS),
+        json: flags.json,
+    })
+}
+
+fn parse_verify_args(args: impl Iterator<Item = String>) -> Result<Command, UsageError> {
+    let mut flags = Flags::parse(args)?;
+    // Verifying everything is the common case, so --all is optional here
+    if flags.day.is_none() {
+        flags.all = true;
+    }
+    let days = flags.days("verify")?;
+
+    if flags.part.is_some() || flags.input.is_some() {
+        return usage_error("--part and --input can only be used with run");
+    }
+    if flags.iterations.is_some() || flags.json {
+        return usage_error("--iterations and --json can only be used with bench");
+    }
+
+    Ok(Command::Verify {
+        days,
+        answers: flags
+            .answers
+            .unwrap_or_else(|| PathBuf::from(DEFAULT_ANSWERS)),
+    })
+}
+
+fn parse_new_day_args(mut args: impl Iterator<Item = String>) -> Result<Command, UsageError> {
+    let value = match (args.next(), args.next()) {
+        (Some(value), None) => value,
+        _ => return usage_error("new-day needs exactly one day number"),
+    };
+
+    match value.parse() {
+        Ok(day) if (1..=25).contains(&day) => Ok(Command::NewDay { day }),
+        _ => usage_error(format!("invalid day '{value}', expected 1 to 25")),
+    }
+}
+
+fn parse_climb_args(mut args: impl Iterator<Item = String>) -> Result<Command, UsageError> {
+    let (mut from, mut to) = (vec![], vec![]);
+    let (mut field, mut unreachable) = (false, false);
+    let mut input = None;
+
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "--from" | "--to" => {
+                let selector = flag_value(&arg, args.next())?.parse().map_err(UsageError)?;
+                if arg == "--from" {
+                    from.push(selector);
+                } else {
+                    to.push(selector);
+                }
+            }
+            "--field" => field = true,
+            "--unreachable" => unreachable = true,
+            "-i" | "--input" => {
+                input = Some(match flag_value(&arg, args.next())?.as_str() {
+                    "-" => InputSource::Stdin,
+                    path => InputSource::File(PathBuf::from(path)),
+                })
+            }
+            other => return usage_error(format!("unexpected argument '{other}'")),
+        }
+    }
+
+    if from.is_empty() {
+        return usage_error("climb needs at least one --from");
+    }
+    if to.is_empty() && !field && !unreachable {
+        return usage_error("climb needs --to, --field or --unreachable");
+    }
+
+    Ok(Command::Climb {
+        from,
+        to,
+        field,
+        unreachable,
+        input,
+    })
+}
+
+/// Parses the command line, not including the program name
+pub fn parse_args(args: impl IntoIterator<Item = String>) -> Result<Command, UsageError> {
+    let mut args = args.into_iter();
+
+    match args.next().as_deref() {
+        Some("run") => parse_run_args(args),
+        Some("bench") => parse_bench_args(args),
+        Some("verify") => parse_verify_args(args),
+        Some("new-day") => parse_new_day_args(args),
+        Some("climb") => parse_climb_args(args),
+        Some("help" | "-h" | "--help") | None => Ok(Command::Help),
+        Some(other) => usage_error(format!("unknown command '{other}'")),
+    }
+}
+
+/// Runs one day against `input`, printing each answer. Returns false if anything failed.
+fn run_day(day: u32, part: Option<Part>, input: &InputSource) -> bool {
+    let registry = registry();
+    let solver = &registry[&day];
+
+    let raw_input = match input.read() {
+        Ok(raw_input) => raw_input,
+        Err(e) => {
+            eprintln!("Day {day}: couldn't read {input}: {e}");
+            return false;
+        }
+    };
+
+    let parsed = match solver.parse(&raw_input) {
+        Ok(parsed) => parsed,
+        Err(e) => {
+            eprintln!("Day {day}: FAILED while parsing {input}:\n{}", e.report());
+            return false;
+        }
+    };
+
+    let parts = match part {
+        Some(part) => vec![part],
+        None => Part::ALL.to_vec(),
+    };
+    for part in parts {
+        println!("Day {day} - Part {part}: {}", parsed.run(part));
+    }
+
+    true
+}
+
+/// Benchmarks each day against its default input. Returns false if any day failed.
+fn bench_days(days: Days, iterations: usize, json: bool) -> bool {
+    let registry = registry();
+    let day_numbers: Vec<u32> = match days {
+        Days::All => registry.keys().copied().collect(),
+        Days::One(day) => vec![day],
+    };
+
+    let mut success = true;
+    let mut results: Vec<DayBench> = vec![];
+    for day in day_numbers {
+        let input = InputSource::default_for(day);
+        let raw_input = match input.read() {
+            Ok(raw_input) => raw_input,
+            Err(e) => {
+                eprintln!("Day {day}: couldn't read {input}: {e}");
+                success = false;
+                continue;
+            }
+        };
+
+        match bench_day(day, registry[&day].as_ref(), &raw_input, iterations) {
+            Ok(result) => {
+                if !json {
+                    println!("{result}");
+                }
+                results.push(result);
+            }
+            Err(e) => {
+                eprintln!("Day {day}: FAILED while parsing {input}:\n{}", e.report());
+                success = false;
+            }
+        }
+    }
+
+    if json {
+        match serde_json::to_string_pretty(&results) {
+            Ok(output) => println!("{output}"),
+            Err(e) => {
+                eprintln!("couldn't serialise benchmark results: {e}");
+                success = false;
+            }
+        }
+    }
+
+    success
+}
+
+/// Checks each day's answers against the recorded ones. Returns false if anything failed.
+fn verify_days(days: Days, answers_path: &Path) -> bool {
+    let answers = match fs::read_to_string(answers_path)
+        .map_err(|e| e.to_string())
+        .and_then(|answers| parse_answers(&answers).map_err(|e| e.to_string()))
+    {
+        Ok(answers) => answers,
+        Err(e) => {
+            eprintln!("couldn't load {}: {e}", answers_path.display());
+            return false;
+        }
+    };
+
+    let registry = registry();
+    let day_numbers: Vec<u32> = match days {
+        Days::All => registry.keys().copied().collect(),
+        Days::One(day) => vec![day],
+    };
+
+    let checks: Vec<Check> = day_numbers
+        .into_iter()
+        .flat_map(|day| {
+            let input = InputSource::default_for(day);
+            match input.read() {
+                Ok(raw_input) => verify_day(day, registry[&day].as_ref(), &raw_input, &answers),
+                Err(e) => error_checks(day, &format!("couldn't read {input}: {e}")),
+            }
+        })
+        .collect();
+
+    let (mut passed, mut failed, mut missing) = (0, 0, 0);
+    for check in &checks {
+        println!("{check}");
+        match check.outcome {
+            Outcome::Pass => passed += 1,
+            Outcome::Fail { .. } | Outcome::Error(_) => failed += 1,
+            Outcome::Missing { .. } => missing += 1,
+        }
+    }
+    println!("\n{passed} passed, {failed} failed, {missing} missing");
+
+    failed == 0
+}
+
+/// Answers a query about the day 12 heightmap. Returns false if it couldn't be answered.
+fn climb(
+    from: &[Selector],
+    to: &[Selector],
+    field: bool,
+    unreachable: bool,
+    input: &InputSource,
+) -> bool {
+    let map = match input.read() {
+        Ok(raw_input) => match Map::parse(&raw_input) {
+            Ok(map) => map,
+            Err(e) => {
+                let report = e.to_parse_error(&raw_input).report();
+                eprintln!("Day 12: FAILED while parsing {input}: {e}\n{report}");
+                return false;
+            }
+        },
+        Err(e) => {
+            eprintln!("Day 12: couldn't read {input}: {e}");
+            return false;
+        }
+    };
+
+    let rules = ClimbingRules::PUZZLE;
+    let sources = map.select(from);
+    if sources.is_empty() {
+        eprintln!("no cells match --from");
+        return false;
+    }
+
+    if !to.is_empty() {
+        match map.shortest_distance(&sources, &map.select(to), &rules) {
+            Some(distance) => println!("Shortest distance: {distance}"),
+            None => println!("No route"),
+        }
+    }
+    if field {
+        println!(
+            "{}",
+            render_distance_field(&map.distance_field(&sources, &rules))
+        );
+    }
+    if unreachable {
+        let cells = map.unreachable(&sources, &rules);
+        println!("{} unreachable cells", cells.len());
+        cells.iter().for_each(|cell| println!("{cell}"));
+    }
+
+    true
+}
+
+/// Executes a parsed command. Returns false if any day failed.
+pub fn execute(command: Command) -> bool {
+    match command {
+        Command::Help => {
+            println!("{USAGE}");
+            true
+        }
+        Command::Run {
+            days: Days::One(day),
+            part,
+            input,
+        } => {
+            let input = input.unwrap_or_else(|| InputSource::default_for(day));
+            run_day(day, part, &input)
+        }
+        Command::Run {
+            days: Days::All,
+            part,
+            ..
+        } => {
+            // Run every day before checking results, so one failure doesn't hide the rest
+            let results: Vec<bool> = registry()
+                .keys()
+                .map(|day| run_day(*day, part, &InputSource::default_for(*day)))
+                .collect();
+            results.into_iter().all(|success| success)
+        }
+        Command::Bench {
+            days,
+            iterations,
+            json,
+        } => bench_days(days, iterations, json),
+        Command::Verify { days, answers } => verify_days(days, &answers),
+        Command::Climb {
+            from,
+            to,
+            field,
+            unreachable,
+            input,
+        } => {
+            let input = input.unwrap_or_else(|| InputSource::default_for(12));
+            climb(&from, &to, field, unreachable, &input)
+        }
+        Command::NewDay { day } => match new_day(Path::new("."), day) {
+            Ok(written) => {
+                written
+                    .iter()
+                    .for_each(|path| println!("wrote {}", path.display()));
+                true
+            }
+            Err(e) => {
+                eprintln!("couldn't create day {day}: {e}");
+                false
+            }
+        },
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::vec2::Vec2;
+
+    fn args(line: &str) -> Vec<String> {
+        line.split_whitespace().map(String::from).collect()
+    }
+
+    #[test]
+    fn parse_run_day_test() {
+        let command = parse_args(args("run --day 11 --part 2 --input -")).unwrap();
+        assert_eq!(
+            command,
+            Command::Run {
+                days: Days::One(11),
+                part: Some(Part::Two),
+                input: Some(InputSource::Stdin),
+            }
+        );
+    }
+
+    #[test]
+    fn parse_run_all_test() {
+        let command = parse_args(args("run -a")).unwrap();
+        assert_eq!(
+            command,
+            Command::Run {
+                days: Days::All,
+                part: None,
+                input: None,
+            }
+        );
+    }
+
+    #[test]
+    fn parse_bench_test() {
+        let command = parse_args(args("bench --all -n 25 --json")).unwrap();
+        assert_eq!(
+            command,
+            Command::Bench {
+                days: Days::All,
+                iterations: 25,
+                json: true,
+            }
+        );
+    }
+
+    #[test]
+    fn parse_verify_test() {
+        assert_eq!(
+            parse_args(args("verify")).unwrap(),
+            Command::Verify {
+                days: Days::All,
+                answers: PathBuf::from(DEFAULT_ANSWERS),
+            }
+        );
+        assert_eq!(
+            parse_args(args("verify --day 9 --answers other.txt")).unwrap(),
+            Command::Verify {
+                days: Days::One(9),
+                answers: PathBuf::from("other.txt"),
+            }
+        );
+    }
+
+    #[test]
+    fn parse_new_day_test() {
+        assert_eq!(
+            parse_args(args("new-day 14")).unwrap(),
+            Command::NewDay { day: 14 }
+        );
+    }
+
+    #[test]
+    fn parse_climb_test() {
+        assert_eq!(
+            parse_args(args("climb --from a --from 0,4 --to E --field")).unwrap(),
+            Command::Climb {
+                from: vec![Selector::Height('a'), Selector::Point(Vec2::new(0, 4))],
+                to: vec![Selector::End],
+                field: true,
+                unreachable: false,
+                input: None,
+            }
+        );
+    }
+
+    #[test]
+    fn parse_errors_test() {
+        assert!(parse_args(args("run")).is_err());
+        assert!(parse_args(args("run --all --day 1")).is_err());
+        assert!(parse_args(args("run --all --input foo.txt")).is_err());
+        assert!(parse_args(args("run --day 26")).is_err());
+        assert!(parse_args(args("run --day 1 --part 3")).is_err());
+        assert!(parse_args(args("run --day")).is_err());
+        assert!(parse_args(args("fly")).is_err());
+        assert!(parse_args(args("run --day 1 --json")).is_err());
+        assert!(parse_args(args("bench --day 1 --part 1")).is_err());
+        assert!(parse_args(args("bench --all --iterations 0")).is_err());
+        assert!(parse_args(args("verify --part 1")).is_err());
+        assert!(parse_args(args("run --day 1 --answers a.txt")).is_err());
+        assert!(parse_args(args("new-day 26")).is_err());
+        assert!(parse_args(args("new-day")).is_err());
+        assert!(parse_args(args("climb --to E")).is_err());
+        assert!(parse_args(args("climb --from S")).is_err());
+        assert!(parse_args(args("climb --from A --to E")).is_err());
+    }
+}
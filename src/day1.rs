@@ -1,19 +1,14 @@
-use nom::bytes::complete::tag;
-use nom::character::complete::char;
-use nom::multi::separated_list1;
-
-use crate::parsers::parse_usize;
+use crate::error::ParseError;
+use crate::parsers::{blocks_of, finish, lines_of, parse_usize};
+use crate::solution::Solution;
 
 type Calories = usize;
 
 type ElfFoodList = Vec<Vec<Calories>>;
 
 #[aoc_generator(day1)]
-fn input_generator(input: &str) -> ElfFoodList {
-    separated_list1(tag("\n\n"), separated_list1(char('\n'), parse_usize))(input)
-        .ok()
-        .unwrap()
-        .1
+fn input_generator(input: &str) -> Result<ElfFoodList, ParseError> {
+    finish(1, input, blocks_of(lines_of(parse_usize)))
 }
 
 #[aoc(day1, part1)]
@@ -32,6 +27,26 @@ fn part2(input: &ElfFoodList) -> usize {
     calories_per_elf.iter().rev().take(3).sum()
 }
 
+pub struct Day1;
+
+impl Solution for Day1 {
+    type Input = ElfFoodList;
+    type Answer1 = usize;
+    type Answer2 = usize;
+
+    fn parse(input: &str) -> Result<Self::Input, ParseError> {
+        input_generator(input)
+    }
+
+    fn part1(input: &Self::Input) -> Self::Answer1 {
+        part1(input)
+    }
+
+    fn part2(input: &Self::Input) -> Self::Answer2 {
+        part2(input)
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -53,13 +68,13 @@ mod tests {
 
     #[test]
     fn part1_test() {
-        let input = input_generator(INPUT_STR);
+        let input = input_generator(INPUT_STR).unwrap();
         assert_eq!(part1(&input), 24000);
     }
 
     #[test]
     fn part2_test() {
-        let input = input_generator(INPUT_STR);
+        let input = input_generator(INPUT_STR).unwrap();
         assert_eq!(part2(&input), 45000);
     }
 }
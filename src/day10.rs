@@ -1,54 +1,414 @@
-struct CPU {
-    x: usize,
+use std::fmt::Display;
+use std::str::FromStr;
+
+use crate::error::ParseError;
+use crate::grid::{Grid, GridError};
+use crate::ocr;
+use crate::solution::Solution;
+use crate::vec2::Vec2;
+
+const SCREEN_WIDTH: usize = 40;
+const SCREEN_HEIGHT: usize = 6;
+
+struct Cpu {
+    /// Indexed by `Register`
+    registers: [isize; Register::ALL.len()],
     cycle: usize,
-    signal_cycles: Vec<usize>,
-    strength_sum: usize,
-    crt_width: usize,
+    schedule: Schedule,
+    samples: Vec<Sample>,
+    /// The CRT's pixels, lit or not. Cycles past the last pixel draw nothing.
+    screen: Grid<bool>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Register {
+    X,
+    Y,
+    Z,
+}
+
+impl Register {
+    const ALL: [Self; 3] = [Self::X, Self::Y, Self::Z];
+
+    fn name(self) -> &'static str {
+        match self {
+            Register::X => "x",
+            Register::Y => "y",
+            Register::Z => "z",
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Operand {
+    Register(Register),
+    Immediate(isize),
+}
+
+impl FromStr for Operand {
+    type Err = ();
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match Register::ALL.iter().find(|register| register.name() == s) {
+            Some(register) => Ok(Self::Register(*register)),
+            None => s.parse().map(Self::Immediate).map_err(|_| ()),
+        }
+    }
+}
+
+impl Display for Operand {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Operand::Register(register) => write!(f, "{}", register.name()),
+            Operand::Immediate(value) => write!(f, "{value}"),
+        }
+    }
+}
+
+/// What an instruction's operand is allowed to be
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum OperandKind {
+    /// A register to write to
+    Register,
+    /// A register to read from, or a number
+    Value,
 }
 
-#[derive(Debug)]
-enum Instruction {
-    Addx { v: isize },
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Opcode {
     Noop,
+    /// Adds its operand to X
+    Addx,
+    /// Adds its second operand to the register in its first
+    Add,
+    /// Sets the register in its first operand to its second
+    Set,
 }
 
-impl CPU {
-    fn new() -> Self {
-        let signal_cycles = vec![20, 60, 100, 140, 180, 220];
+pub struct InstructionSpec {
+    pub opcode: Opcode,
+    pub mnemonic: &'static str,
+    pub operands: &'static [OperandKind],
+    /// How many cycles the instruction takes. Its effect is applied once they've all run.
+    pub cycles: usize,
+}
+
+pub const INSTRUCTION_SET: [InstructionSpec; 4] = [
+    InstructionSpec {
+        opcode: Opcode::Noop,
+        mnemonic: "noop",
+        operands: &[],
+        cycles: 1,
+    },
+    InstructionSpec {
+        opcode: Opcode::Addx,
+        mnemonic: "addx",
+        operands: &[OperandKind::Value],
+        cycles: 2,
+    },
+    InstructionSpec {
+        opcode: Opcode::Add,
+        mnemonic: "add",
+        operands: &[OperandKind::Register, OperandKind::Value],
+        cycles: 2,
+    },
+    InstructionSpec {
+        opcode: Opcode::Set,
+        mnemonic: "set",
+        operands: &[OperandKind::Register, OperandKind::Value],
+        cycles: 1,
+    },
+];
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Instruction {
+    pub opcode: Opcode,
+    pub operands: Vec<Operand>,
+}
+
+impl Instruction {
+    pub fn spec(&self) -> &'static InstructionSpec {
+        INSTRUCTION_SET
+            .iter()
+            .find(|spec| spec.opcode == self.opcode)
+            .expect("every opcode is in the instruction set")
+    }
+
+    /// Parses one line of assembly. On failure, returns the 0-based byte offset of the problem.
+    fn parse(line: &str) -> Result<Self, usize> {
+        let mut tokens = line
+            .split(' ')
+            .scan(0, |offset, token| {
+                let start = *offset;
+                *offset += token.len() + 1;
+                Some((start, token))
+            })
+            .filter(|(_, token)| !token.is_empty());
+
+        let (_, mnemonic) = tokens.next().ok_or(0_usize)?;
+        let spec = INSTRUCTION_SET
+            .iter()
+            .find(|spec| spec.mnemonic == mnemonic)
+            .ok_or(0_usize)?;
+
+        let mut operands = vec![];
+        for kind in spec.operands {
+            let (offset, token) = tokens.next().ok_or(line.len())?;
+            match (kind, token.parse()) {
+                (OperandKind::Register, Ok(operand @ Operand::Register(_)))
+                | (OperandKind::Value, Ok(operand)) => operands.push(operand),
+                _ => return Err(offset),
+            }
+        }
+        if let Some((offset, _)) = tokens.next() {
+            return Err(offset);
+        }
+
+        Ok(Self {
+            opcode: spec.opcode,
+            operands,
+        })
+    }
+}
+
+impl Display for Instruction {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.spec().mnemonic)?;
+        for operand in &self.operands {
+            write!(f, " {operand}")?;
+        }
+        Ok(())
+    }
+}
+
+/// Turns a program back into assembly, one instruction per line
+pub fn disassemble(program: &[Instruction]) -> String {
+    program
+        .iter()
+        .map(|instruction| instruction.to_string())
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+/// Which cycles to measure the signal during
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Schedule {
+    Cycles(Vec<usize>),
+    /// `count` cycles, `step` apart, starting at `start`
+    Every {
+        start: usize,
+        step: usize,
+        count: usize,
+    },
+}
+
+impl Schedule {
+    /// The 20th cycle and every 40 after that, up to the 220th
+    pub const PUZZLE: Self = Self::Every {
+        start: 20,
+        step: 40,
+        count: 6,
+    };
+
+    fn contains(&self, cycle: usize) -> bool {
+        match self {
+            Schedule::Cycles(cycles) => cycles.contains(&cycle),
+            Schedule::Every { start, step, count } => {
+                let Some(since_start) = cycle.checked_sub(*start) else {
+                    return false;
+                };
+                match step {
+                    0 => since_start == 0 && *count > 0,
+                    step => since_start % step == 0 && since_start / step < *count,
+                }
+            }
+        }
+    }
+}
+
+/// The signal measured during one cycle
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Sample {
+    pub cycle: usize,
+    pub x: isize,
+    /// The cycle number multiplied by X
+    pub strength: isize,
+}
+
+/// What happened during one clock cycle
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TraceEntry {
+    pub cycle: usize,
+    /// The instruction being executed during the cycle
+    pub instruction: Instruction,
+    pub x_during: isize,
+    /// X at the end of the cycle, which only differs from `x_during` on an instruction's last cycle
+    pub x_after: isize,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Breakpoint {
+    /// Stop once this cycle has run
+    Cycle(usize),
+    /// Stop once X changes to this value
+    X(isize),
+}
+
+impl Breakpoint {
+    fn is_hit_by(&self, entry: &TraceEntry) -> bool {
+        match self {
+            Breakpoint::Cycle(cycle) => entry.cycle == *cycle,
+            Breakpoint::X(x) => entry.x_during != *x && entry.x_after == *x,
+        }
+    }
+}
+
+/// Runs a program one instruction at a time, recording every cycle and stopping at breakpoints.
+/// The CPU only stops between instructions, so a breakpoint hit during the first cycle of an
+/// `addx` stops after its second.
+pub struct Debugger<'a> {
+    cpu: Cpu,
+    program: &'a [Instruction],
+    next: usize,
+    breakpoints: Vec<Breakpoint>,
+    trace: Vec<TraceEntry>,
+}
+
+impl<'a> Debugger<'a> {
+    pub fn new(program: &'a [Instruction]) -> Self {
         Self {
-            x: 1,
-            cycle: 1,
-            signal_cycles,
-            strength_sum: 0,
-            crt_width: 40,
+            cpu: Cpu::new(),
+            program,
+            next: 0,
+            breakpoints: vec![],
+            trace: vec![],
         }
     }
 
-    fn cycle_to_x(&self) -> usize {
-        let cycle_x_pos = self.cycle % self.crt_width - 1;
-        cycle_x_pos
+    pub fn break_on(&mut self, breakpoint: Breakpoint) {
+        self.breakpoints.push(breakpoint);
     }
 
-    fn draw(&self) {
-        let cycle_x_pos = self.cycle_to_x();
-        if cycle_x_pos == 0 {
-            print!("\n");
+    /// Runs the next instruction, returning the cycles it took, or `None` if the program is done
+    pub fn step(&mut self) -> Option<&[TraceEntry]> {
+        let instruction = self.program.get(self.next)?;
+        self.next += 1;
+
+        let first_cycle = self.cpu.cycle;
+        let x_during = self.cpu.x();
+        self.cpu.execute(instruction);
+        let last_cycle = self.cpu.cycle - 1;
+        let x_after = self.cpu.x();
+
+        let start = self.trace.len();
+        self.trace
+            .extend((first_cycle..=last_cycle).map(|cycle| TraceEntry {
+                cycle,
+                instruction: instruction.clone(),
+                x_during,
+                x_after: if cycle == last_cycle {
+                    x_after
+                } else {
+                    x_during
+                },
+            }));
+        Some(&self.trace[start..])
+    }
+
+    /// Runs until a breakpoint is hit, returning it, or until the program ends, returning `None`
+    pub fn run(&mut self) -> Option<Breakpoint> {
+        let breakpoints = self.breakpoints.clone();
+        while let Some(entries) = self.step() {
+            let hit = breakpoints
+                .iter()
+                .find(|breakpoint| entries.iter().any(|entry| breakpoint.is_hit_by(entry)));
+            if hit.is_some() {
+                return hit.copied();
+            }
         }
-        let x = self.x;
 
-        let visible_xs = vec![x - 1, x, x + 1];
-        if visible_xs.contains(&cycle_x_pos) {
-            print!("#");
-        } else {
-            print!(".");
+        None
+    }
+
+    /// The cycle that will run next
+    pub fn cycle(&self) -> usize {
+        self.cpu.cycle
+    }
+
+    pub fn x(&self) -> isize {
+        self.cpu.x()
+    }
+
+    pub fn trace(&self) -> &[TraceEntry] {
+        &self.trace
+    }
+
+    /// Every cycle run so far as CSV, with a header row
+    pub fn trace_csv(&self) -> String {
+        let mut csv = String::from("cycle,instruction,x_during,x_after\n");
+        for entry in &self.trace {
+            csv += &format!(
+                "{},{},{},{}\n",
+                entry.cycle, entry.instruction, entry.x_during, entry.x_after
+            );
         }
+        csv
+    }
+}
+
+impl Cpu {
+    fn new() -> Self {
+        Self::with_screen(SCREEN_WIDTH, SCREEN_HEIGHT).expect("the puzzle's screen has pixels")
+    }
+
+    fn with_screen(width: usize, height: usize) -> Result<Self, GridError> {
+        Ok(Self {
+            registers: [1, 0, 0],
+            cycle: 1,
+            schedule: Schedule::PUZZLE,
+            samples: vec![],
+            screen: Grid::filled(width, height, false)?,
+        })
+    }
+
+    /// The pixel being drawn during the current cycle
+    fn cycle_to_pixel(&self) -> Vec2 {
+        let width = self.screen.width();
+        Vec2::from_indices((self.cycle - 1) % width, (self.cycle - 1) / width)
+    }
+
+    fn draw(&mut self) {
+        let pixel = self.cycle_to_pixel();
+        // The sprite is 3 pixels wide, centred on X. Only the part of it that's on the screen is
+        // drawn, so X of -1 or the screen width still lights the pixel at the edge.
+        let lit = self.x().abs_diff(pixel.x) <= 1;
+        if let Some(cell) = self.screen.get_mut(pixel) {
+            *cell = lit;
+        }
+    }
+
+    /// The screen as rows of `#` for lit pixels and `.` for dark ones
+    fn render(&self) -> String {
+        self.screen
+            .rows()
+            .map(|row| {
+                row.iter()
+                    .map(|lit| if *lit { '#' } else { '.' })
+                    .collect::<String>()
+            })
+            .collect::<Vec<_>>()
+            .join("\n")
     }
 
     fn tick(&mut self) {
-        if self.signal_cycles.contains(&self.cycle) {
-            let signal_strength = self.signal_strength();
-            self.strength_sum += signal_strength;
-        };
+        if self.schedule.contains(self.cycle) {
+            let x = self.x();
+            self.samples.push(Sample {
+                cycle: self.cycle,
+                x,
+                strength: (self.cycle as isize).wrapping_mul(x),
+            });
+        }
         self.draw();
         self.cycle += 1;
     }
@@ -59,50 +419,121 @@ impl CPU {
         }
     }
 
-    fn execute(&mut self, instruction: &Instruction) {
-        match instruction {
-            Instruction::Addx { v } => {
-                self.tick_n(2);
-                self.x = (self.x as isize + v) as usize;
-            }
-            Instruction::Noop => self.tick_n(1),
+    fn x(&self) -> isize {
+        self.registers[Register::X as usize]
+    }
+
+    fn value(&self, operand: Operand) -> isize {
+        match operand {
+            Operand::Register(register) => self.registers[register as usize],
+            Operand::Immediate(value) => value,
         }
     }
 
-    fn signal_strength(&self) -> usize {
-        self.cycle * (self.x as usize)
+    /// Runs an instruction. Registers wrap on overflow rather than panicking.
+    fn execute(&mut self, instruction: &Instruction) {
+        self.tick_n(instruction.spec().cycles);
+
+        let operands = &instruction.operands;
+        let (register, value) = match (instruction.opcode, operands.as_slice()) {
+            (Opcode::Noop, _) => return,
+            (Opcode::Addx, &[value]) => (Register::X, self.x().wrapping_add(self.value(value))),
+            (Opcode::Add, &[Operand::Register(register), value]) => (
+                register,
+                self.value(Operand::Register(register))
+                    .wrapping_add(self.value(value)),
+            ),
+            (Opcode::Set, &[Operand::Register(register), value]) => (register, self.value(value)),
+            _ => unreachable!("{instruction} doesn't match its spec"),
+        };
+        self.registers[register as usize] = value;
     }
 }
 
 #[aoc_generator(day10)]
-fn input_generator(input: &str) -> Vec<Instruction> {
+fn input_generator(input: &str) -> Result<Vec<Instruction>, ParseError> {
     input
-        .split("\n")
-        .map(|line| {
-            let parts: Vec<_> = line.split(" ").collect();
-            if parts.len() > 1 {
-                let v = parts[1].parse().expect("Failed to parse");
-                Instruction::Addx { v }
-            } else {
-                Instruction::Noop
-            }
+        .split('\n')
+        .enumerate()
+        .map(|(index, line)| {
+            Instruction::parse(line)
+                .map_err(|offset| ParseError::new(10, index + 1, offset + 1, line))
         })
         .collect()
 }
 
-#[aoc(day10, part1)]
-fn part1(input: &[Instruction]) -> usize {
-    let mut cpu = CPU::new();
+fn run(input: &[Instruction]) -> Cpu {
+    run_on(Cpu::new(), input)
+}
 
+fn run_on(mut cpu: Cpu, input: &[Instruction]) -> Cpu {
     input
         .iter()
         .for_each(|instruction| cpu.execute(instruction));
 
-    cpu.strength_sum
+    cpu
+}
+
+/// The signal during every cycle `schedule` picks out, in cycle order
+pub fn sample(input: &[Instruction], schedule: Schedule) -> Vec<Sample> {
+    let cpu = Cpu {
+        schedule,
+        ..Cpu::new()
+    };
+    run_on(cpu, input).samples
+}
+
+#[aoc(day10, part1)]
+fn part1(input: &[Instruction]) -> isize {
+    sample(input, Schedule::PUZZLE)
+        .iter()
+        .fold(0, |sum, sample| sum.wrapping_add(sample.strength))
+}
+
+/// What the program draws on the puzzle's 40x6 screen, as rows of `#` and `.`
+pub fn render_screen(input: &[Instruction]) -> String {
+    run(input).render()
+}
+
+/// What the program draws on a `width` by `height` screen. Drawing stops once the beam has
+/// passed the last pixel.
+pub fn render_screen_with(
+    input: &[Instruction],
+    width: usize,
+    height: usize,
+) -> Result<String, GridError> {
+    Ok(run_on(Cpu::with_screen(width, height)?, input).render())
+}
+
+#[aoc(day10, part2)]
+fn part2(input: &[Instruction]) -> String {
+    ocr::read_letters(&run(input).screen)
+}
+
+pub struct Day10;
+
+impl Solution for Day10 {
+    type Input = Vec<Instruction>;
+    type Answer1 = isize;
+    type Answer2 = String;
+
+    fn parse(input: &str) -> Result<Self::Input, ParseError> {
+        input_generator(input)
+    }
+
+    fn part1(input: &Self::Input) -> Self::Answer1 {
+        part1(input)
+    }
+
+    fn part2(input: &Self::Input) -> Self::Answer2 {
+        part2(input)
+    }
 }
 
 #[cfg(test)]
 mod tests {
+    use proptest::prelude::*;
+
     use super::*;
 
     const INPUT_STR: &str = r"addx 15
@@ -254,7 +685,188 @@ noop";
 
     #[test]
     fn part1_test() {
-        let input = input_generator(INPUT_STR);
+        let input = input_generator(INPUT_STR).unwrap();
         assert_eq!(part1(&input), 13140);
     }
+
+    #[test]
+    fn render_screen_test() {
+        let input = input_generator(INPUT_STR).unwrap();
+        assert_eq!(
+            render_screen(&input),
+            "##..##..##..##..##..##..##..##..##..##..
+###...###...###...###...###...###...###.
+####....####....####....####....####....
+#####.....#####.....#####.....#####.....
+######......######......######......####
+#######.......#######.......#######....."
+        );
+    }
+
+    #[test]
+    fn screen_size_test() {
+        let input = input_generator(&["noop"; 10].join("\n")).unwrap();
+        assert_eq!(
+            render_screen_with(&input, 4, 2),
+            Ok(String::from("###.\n###."))
+        );
+        assert_eq!(render_screen_with(&input, 0, 6), Err(GridError::Empty));
+    }
+
+    #[test]
+    fn trace_test() {
+        let input = input_generator("noop\naddx 3\naddx -5").unwrap();
+        let mut debugger = Debugger::new(&input);
+        assert_eq!(debugger.run(), None);
+        assert_eq!(debugger.cycle(), 6);
+        assert_eq!(debugger.x(), -1);
+        assert_eq!(
+            debugger.trace_csv(),
+            "cycle,instruction,x_during,x_after
+1,noop,1,1
+2,addx 3,1,1
+3,addx 3,1,4
+4,addx -5,4,4
+5,addx -5,4,-1
+"
+        );
+    }
+
+    #[test]
+    fn breakpoint_test() {
+        let input = input_generator(INPUT_STR).unwrap();
+        let mut debugger = Debugger::new(&input);
+        debugger.break_on(Breakpoint::Cycle(20));
+        debugger.break_on(Breakpoint::X(-1));
+
+        assert_eq!(debugger.run(), Some(Breakpoint::Cycle(20)));
+        let entry = &debugger.trace()[19];
+        assert_eq!((entry.cycle, entry.x_during), (20, 21));
+
+        assert_eq!(debugger.run(), Some(Breakpoint::X(-1)));
+        assert_eq!(debugger.x(), -1);
+        assert_eq!(debugger.trace().last().unwrap().x_after, -1);
+    }
+
+    #[test]
+    fn instruction_set_test() {
+        let program = input_generator("set y 5\nadd y -2\naddx y\nnoop").unwrap();
+        assert_eq!(
+            program[1],
+            Instruction {
+                opcode: Opcode::Add,
+                operands: vec![Operand::Register(Register::Y), Operand::Immediate(-2)],
+            }
+        );
+
+        let mut debugger = Debugger::new(&program);
+        debugger.run();
+        assert_eq!(debugger.x(), 4);
+        assert_eq!(debugger.cycle(), 7);
+    }
+
+    #[test]
+    fn invalid_instruction_test() {
+        let error = |input| input_generator(input).err().unwrap();
+        assert_eq!(error("noop\njmp 4"), ParseError::new(10, 2, 1, "jmp 4"));
+        assert_eq!(error("addx"), ParseError::new(10, 1, 5, "addx"));
+        assert_eq!(error("addx 1 2"), ParseError::new(10, 1, 8, "addx 1 2"));
+        assert_eq!(error("set 3 4"), ParseError::new(10, 1, 5, "set 3 4"));
+        assert_eq!(error("add x q"), ParseError::new(10, 1, 7, "add x q"));
+    }
+
+    #[test]
+    fn disassemble_test() {
+        let program = input_generator(INPUT_STR).unwrap();
+        assert_eq!(disassemble(&program), INPUT_STR);
+
+        let source = "set z -7\nadd x z\naddx 3\nnoop";
+        assert_eq!(disassemble(&input_generator(source).unwrap()), source);
+    }
+
+    #[test]
+    fn sample_test() {
+        let input = input_generator(INPUT_STR).unwrap();
+        let samples = sample(&input, Schedule::PUZZLE);
+        let strengths: Vec<isize> = samples.iter().map(|sample| sample.strength).collect();
+        assert_eq!(strengths, vec![420, 1140, 1800, 2940, 2880, 3960]);
+        assert_eq!(
+            samples[2],
+            Sample {
+                cycle: 100,
+                x: 18,
+                strength: 1800,
+            }
+        );
+
+        let explicit = sample(&input, Schedule::Cycles(vec![1, 2, 3, 220]));
+        let cycles: Vec<usize> = explicit.iter().map(|sample| sample.cycle).collect();
+        assert_eq!(cycles, vec![1, 2, 3, 220]);
+        assert_eq!(explicit[0].x, 1);
+
+        let every = Schedule::Every {
+            start: 5,
+            step: 10,
+            count: 3,
+        };
+        let cycles: Vec<usize> = sample(&input, every).iter().map(|s| s.cycle).collect();
+        assert_eq!(cycles, vec![5, 15, 25]);
+    }
+
+    #[test]
+    fn sprite_off_screen_test() {
+        // Half off the left edge, then half off the right, then nowhere near the screen
+        let mut cpu = Cpu::with_screen(4, 2).unwrap();
+        cpu.registers[Register::X as usize] = -1;
+        cpu.tick_n(2);
+        cpu.registers[Register::X as usize] = 4;
+        cpu.tick_n(2);
+        cpu.registers[Register::X as usize] = isize::MIN;
+        cpu.tick_n(4);
+        assert_eq!(cpu.render(), "#..#\n....");
+    }
+
+    fn instruction() -> impl Strategy<Value = Instruction> {
+        let register = prop::sample::select(Register::ALL.to_vec()).prop_map(Operand::Register);
+        let value = prop_oneof![
+            register.clone(),
+            any::<isize>().prop_map(Operand::Immediate)
+        ];
+
+        prop_oneof![
+            Just(Instruction {
+                opcode: Opcode::Noop,
+                operands: vec![],
+            }),
+            value.clone().prop_map(|value| Instruction {
+                opcode: Opcode::Addx,
+                operands: vec![value],
+            }),
+            (register.clone(), value.clone()).prop_map(|(register, value)| Instruction {
+                opcode: Opcode::Add,
+                operands: vec![register, value],
+            }),
+            (register, value).prop_map(|(register, value)| Instruction {
+                opcode: Opcode::Set,
+                operands: vec![register, value],
+            }),
+        ]
+    }
+
+    proptest! {
+        #[test]
+        fn random_programs_never_panic(program in prop::collection::vec(instruction(), 0..400)) {
+            let source = disassemble(&program);
+            if !program.is_empty() {
+                prop_assert_eq!(&input_generator(&source).unwrap(), &program);
+            }
+
+            part1(&program);
+            part2(&program);
+            let mut debugger = Debugger::new(&program);
+            debugger.break_on(Breakpoint::X(0));
+            while debugger.run().is_some() {}
+            prop_assert_eq!(debugger.trace().len() + 1, debugger.cycle());
+        }
+    }
 }
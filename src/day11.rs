@@ -1,12 +1,20 @@
+use std::cmp::Reverse;
+use std::collections::{BTreeMap, HashMap};
+use std::error::Error;
+use std::fmt::Display;
+
 use nom::branch::alt;
 use nom::bytes::complete::tag;
-use nom::character::complete::{char, digit1, multispace1, space1};
-use nom::combinator::{map_res, recognize};
-use nom::multi::separated_list1;
-use nom::sequence::terminated;
+use nom::character::complete::{alpha1, char, multispace1, one_of, space0};
+use nom::combinator::{map, map_opt, verify};
+use nom::multi::{fold_many0, separated_list1};
+use nom::sequence::{delimited, pair, terminated};
 use nom::IResult;
+use num_bigint::BigInt;
 
-use crate::parsers::parse_usize;
+use crate::error::ParseError;
+use crate::parsers::{blocks_of, finish, parse_usize};
+use crate::solution::Solution;
 
 fn monkey_id(input: &str) -> IResult<&str, usize> {
     let (input, _) = tag("Monkey ")(input)?;
@@ -19,43 +27,69 @@ fn monkey_id(input: &str) -> IResult<&str, usize> {
 fn starting_items(input: &str) -> IResult<&str, Vec<usize>> {
     let (input, _) = multispace1(input)?;
     let (input, _) = tag("Starting items: ")(input)?;
-    separated_list1(tag(", "), map_res(digit1, str::parse::<usize>))(input)
+    separated_list1(tag(", "), parse_usize)(input)
+}
+
+/// A number, a variable, or a parenthesised expression
+fn atom(input: &str) -> IResult<&str, Expr> {
+    delimited(
+        space0,
+        alt((
+            map(parse_usize, Expr::Constant),
+            map(alpha1, |name| Expr::Variable(String::from(name))),
+            delimited(char('('), expression, char(')')),
+        )),
+        space0,
+    )(input)
+}
+
+/// Atoms joined by `*` and `/`, which bind tighter than `+` and `-`
+fn term(input: &str) -> IResult<&str, Expr> {
+    let (input, first) = atom(input)?;
+    fold_many0(
+        pair(map_opt(one_of("*/"), BinaryOp::from_symbol), atom),
+        move || first.clone(),
+        |lhs, (op, rhs)| Expr::Binary(Box::new(lhs), op, Box::new(rhs)),
+    )(input)
+}
+
+/// Terms joined by `+` and `-`, all left associative
+fn expression(input: &str) -> IResult<&str, Expr> {
+    let (input, first) = term(input)?;
+    fold_many0(
+        pair(map_opt(one_of("+-"), BinaryOp::from_symbol), term),
+        move || first.clone(),
+        |lhs, (op, rhs)| Expr::Binary(Box::new(lhs), op, Box::new(rhs)),
+    )(input)
 }
 
 fn operation(input: &str) -> IResult<&str, Operation> {
     let (input, _) = multispace1(input)?;
-    let (input, _) = tag("Operation: new = old ")(input)?;
-    let (input, symbol) = alt((char('*'), char('+')))(input)?;
-    let (input, _) = space1(input)?;
-    let (input, value) = alt((tag("old"), digit1))(input)?;
-
-    let operation = match value {
-        "old" => Operation::OldSquared,
-        v => {
-            let val = str::parse(v).expect("Bad value");
-            Operation::from((symbol, Some(val)))
-        }
-    };
+    let (input, _) = tag("Operation: new = ")(input)?;
+    let (input, expression) = verify(expression, |expression: &Expr| {
+        expression.variables().iter().all(|name| *name == "old")
+    })(input)?;
 
-    IResult::Ok((input, operation))
+    IResult::Ok((input, Operation(expression)))
 }
 
 fn test_divisible(input: &str) -> IResult<&str, usize> {
     let (input, _) = multispace1(input)?;
     let (input, _) = tag("Test: divisible by ")(input)?;
-    map_res(recognize(digit1), str::parse)(input)
+    // Nothing is divisible by zero, so it can't be a test
+    verify(parse_usize, |&divisor| divisor > 0)(input)
 }
 
 fn if_true(input: &str) -> IResult<&str, usize> {
     let (input, _) = multispace1(input)?;
     let (input, _) = tag("If true: throw to monkey ")(input)?;
-    map_res(recognize(digit1), str::parse)(input)
+    parse_usize(input)
 }
 
 fn if_false(input: &str) -> IResult<&str, usize> {
     let (input, _) = multispace1(input)?;
     let (input, _) = tag("If false: throw to monkey ")(input)?;
-    map_res(recognize(digit1), str::parse)(input)
+    parse_usize(input)
 }
 
 fn test_whole(input: &str) -> IResult<&str, Test> {
@@ -84,42 +118,210 @@ fn monkey_whole(input: &str) -> IResult<&str, Monkey> {
         items,
         operation,
         test,
-        inspect_count: 0,
     };
 
     IResult::Ok((input, monkey))
 }
 
-#[derive(Debug, Clone)]
-enum Operation {
-    OldAdd(usize),
-    OldMultiply(usize),
-    OldSquared,
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum BinaryOp {
+    Add,
+    Subtract,
+    Multiply,
+    Divide,
 }
 
-impl Operation {
-    fn apply(&self, old: usize) -> usize {
+impl BinaryOp {
+    fn from_symbol(symbol: char) -> Option<Self> {
+        match symbol {
+            '+' => Some(Self::Add),
+            '-' => Some(Self::Subtract),
+            '*' => Some(Self::Multiply),
+            '/' => Some(Self::Divide),
+            _ => None,
+        }
+    }
+
+    fn symbol(self) -> char {
+        match self {
+            BinaryOp::Add => '+',
+            BinaryOp::Subtract => '-',
+            BinaryOp::Multiply => '*',
+            BinaryOp::Divide => '/',
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum EvalError {
+    UnboundVariable(String),
+    /// A worry level, or a constant or starting item, doesn't fit in an `isize`
+    Overflow,
+    DivisionByZero,
+}
+
+impl Display for EvalError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            EvalError::UnboundVariable(name) => write!(f, "unknown variable '{name}'"),
+            EvalError::Overflow => write!(f, "overflow"),
+            EvalError::DivisionByZero => write!(f, "division by zero"),
+        }
+    }
+}
+
+impl Error for EvalError {}
+
+/// A worry level: either a plain `isize`, kept small by reducing it modulo the product of the
+/// monkeys' divisors, or an exact [`BigInt`] that is never reduced. Worry levels are signed, so
+/// an operation like `new = 100 - old` can take them below zero.
+pub trait Worry: Clone + Default + Display + PartialEq {
+    /// Fails with [`EvalError::Overflow`] if `value` doesn't fit
+    fn from_usize(value: usize) -> Result<Self, EvalError>;
+    fn checked_add(&self, rhs: &Self) -> Option<Self>;
+    fn checked_sub(&self, rhs: &Self) -> Option<Self>;
+    fn checked_mul(&self, rhs: &Self) -> Option<Self>;
+    /// Rounds towards zero
+    fn checked_div(&self, rhs: &Self) -> Option<Self>;
+    /// The remainder from 0 to `modulus - 1`, even for negative worry levels
+    fn remainder(&self, modulus: usize) -> usize;
+}
+
+impl Worry for isize {
+    fn from_usize(value: usize) -> Result<Self, EvalError> {
+        isize::try_from(value).map_err(|_| EvalError::Overflow)
+    }
+
+    fn checked_add(&self, rhs: &Self) -> Option<Self> {
+        isize::checked_add(*self, *rhs)
+    }
+
+    fn checked_sub(&self, rhs: &Self) -> Option<Self> {
+        isize::checked_sub(*self, *rhs)
+    }
+
+    fn checked_mul(&self, rhs: &Self) -> Option<Self> {
+        isize::checked_mul(*self, *rhs)
+    }
+
+    fn checked_div(&self, rhs: &Self) -> Option<Self> {
+        isize::checked_div(*self, *rhs)
+    }
+
+    fn remainder(&self, modulus: usize) -> usize {
+        // `i128` holds both sides exactly, whatever the modulus
+        (*self as i128).rem_euclid(modulus as i128) as usize
+    }
+}
+
+impl Worry for BigInt {
+    fn from_usize(value: usize) -> Result<Self, EvalError> {
+        Ok(BigInt::from(value))
+    }
+
+    fn checked_add(&self, rhs: &Self) -> Option<Self> {
+        Some(self + rhs)
+    }
+
+    fn checked_sub(&self, rhs: &Self) -> Option<Self> {
+        Some(self - rhs)
+    }
+
+    fn checked_mul(&self, rhs: &Self) -> Option<Self> {
+        Some(self * rhs)
+    }
+
+    fn checked_div(&self, rhs: &Self) -> Option<Self> {
+        (*rhs != BigInt::ZERO).then(|| self / rhs)
+    }
+
+    fn remainder(&self, modulus: usize) -> usize {
+        let modulus = BigInt::from(modulus);
+        // `%` keeps the sign of the worry level, so shift negative remainders up
+        let remainder = (self % &modulus + &modulus) % &modulus;
+        usize::try_from(remainder).expect("remainder is smaller than the modulus")
+    }
+}
+
+/// The right hand side of a monkey's `new = ...` line
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Expr {
+    Constant(usize),
+    Variable(String),
+    Binary(Box<Expr>, BinaryOp, Box<Expr>),
+}
+
+impl Expr {
+    pub fn parse(input: &str) -> Result<Self, ParseError> {
+        finish(11, input, expression)
+    }
+
+    /// Every variable used, in order of appearance
+    pub fn variables(&self) -> Vec<&str> {
+        match self {
+            Expr::Constant(_) => vec![],
+            Expr::Variable(name) => vec![name],
+            Expr::Binary(lhs, _, rhs) => {
+                let mut variables = lhs.variables();
+                variables.extend(rhs.variables());
+                variables
+            }
+        }
+    }
+
+    /// Works out the value, looking up variables with `lookup`. Division rounds towards zero.
+    pub fn evaluate<W: Worry>(&self, lookup: &impl Fn(&str) -> Option<W>) -> Result<W, EvalError> {
         match self {
-            Operation::OldAdd(val) => old + val,
-            Operation::OldMultiply(val) => old * val,
-            Operation::OldSquared => old * old,
+            Expr::Constant(value) => W::from_usize(*value),
+            Expr::Variable(name) => {
+                lookup(name).ok_or_else(|| EvalError::UnboundVariable(name.clone()))
+            }
+            Expr::Binary(lhs, op, rhs) => {
+                let (lhs, rhs) = (lhs.evaluate(lookup)?, rhs.evaluate(lookup)?);
+                match op {
+                    BinaryOp::Add => lhs.checked_add(&rhs).ok_or(EvalError::Overflow),
+                    BinaryOp::Subtract => lhs.checked_sub(&rhs).ok_or(EvalError::Overflow),
+                    BinaryOp::Multiply => lhs.checked_mul(&rhs).ok_or(EvalError::Overflow),
+                    BinaryOp::Divide if rhs == W::default() => Err(EvalError::DivisionByZero),
+                    BinaryOp::Divide => lhs.checked_div(&rhs).ok_or(EvalError::Overflow),
+                }
+            }
         }
     }
 }
 
-impl From<(char, Option<usize>)> for Operation {
-    fn from((c, val): (char, Option<usize>)) -> Self {
-        match val {
-            Some(v) => match c {
-                '*' => Self::OldMultiply(v),
-                '+' => Self::OldAdd(v),
-                _ => panic!("Bad operation symbol"),
-            },
-            None => Self::OldSquared,
+impl Display for Expr {
+    /// Nested operations are always parenthesised, so precedence never has to be worked out
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Expr::Constant(value) => write!(f, "{value}"),
+            Expr::Variable(name) => write!(f, "{name}"),
+            Expr::Binary(lhs, op, rhs) => {
+                for (side, expr) in [(0, lhs), (1, rhs)] {
+                    if side == 1 {
+                        write!(f, " {} ", op.symbol())?;
+                    }
+                    match expr.as_ref() {
+                        Expr::Binary(..) => write!(f, "({expr})")?,
+                        _ => write!(f, "{expr}")?,
+                    }
+                }
+                Ok(())
+            }
         }
     }
 }
 
+#[derive(Debug, Clone)]
+struct Operation(Expr);
+
+impl Operation {
+    fn apply<W: Worry>(&self, old: &W) -> Result<W, EvalError> {
+        self.0
+            .evaluate(&|name| (name == "old").then(|| old.clone()))
+    }
+}
+
 #[derive(Debug, Clone)]
 struct Test {
     divisible_by: usize,
@@ -128,8 +330,8 @@ struct Test {
 }
 
 impl Test {
-    fn apply(&self, worry_level: usize) -> usize {
-        let result = worry_level % self.divisible_by;
+    fn apply<W: Worry>(&self, worry_level: &W) -> usize {
+        let result = worry_level.remainder(self.divisible_by);
 
         match result {
             0 => self.true_to,
@@ -139,115 +341,466 @@ impl Test {
 }
 
 #[derive(Debug, Clone)]
-struct Monkey {
+pub struct Monkey {
     items: Vec<usize>,
     operation: Operation,
     test: Test,
-    inspect_count: usize,
-}
-
-struct Throw {
-    worry_level: usize,
-    to_monkey_id: usize,
 }
 
 impl Monkey {
-    fn inspect(&mut self, lcm: usize, disable_worry_division: bool) -> Throw {
-        let old_worry_level = self.items.get(0).expect("No element and index 0");
-        let mut worry_level = self.operation.apply(*old_worry_level);
-        if !disable_worry_division {
-            worry_level /= 3;
+    /// The worry level of an item after this monkey has inspected it. Worry levels are divided
+    /// by `relief`, then reduced modulo `modulus` if there is one.
+    fn inspect<W: Worry>(
+        &self,
+        old: &W,
+        relief: usize,
+        modulus: Option<usize>,
+    ) -> Result<W, EvalError> {
+        let mut worry_level = self.operation.apply(old)?;
+        if relief != 1 {
+            worry_level = worry_level
+                .checked_div(&W::from_usize(relief)?)
+                .ok_or(EvalError::DivisionByZero)?;
         }
-        if worry_level >= lcm {
-            worry_level %= lcm;
+        if let Some(modulus) = modulus {
+            worry_level = W::from_usize(worry_level.remainder(modulus))?;
         }
-        let to_monkey_id = self.test.apply(worry_level);
+        Ok(worry_level)
+    }
+}
 
-        // Pop from front of items
-        let new_items = &self.items[1..];
-        self.items = new_items.to_vec();
+/// One item changing hands, with the worry level it was thrown with
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Throw<W> {
+    /// Rounds count from 1
+    pub round: usize,
+    pub from: usize,
+    pub to: usize,
+    pub worry_level: W,
+}
 
-        self.inspect_count += 1;
+/// Where an item started and every throw it's been part of, in order
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Lineage<W> {
+    pub start: usize,
+    pub throws: Vec<Throw<W>>,
+}
 
-        Throw {
-            worry_level,
-            to_monkey_id,
-        }
+impl<W> Lineage<W> {
+    /// The monkey holding the item once `round` is over, or at the start if `round` is 0
+    pub fn holder_after(&self, round: usize) -> usize {
+        self.throws
+            .iter()
+            .take_while(|throw| throw.round <= round)
+            .last()
+            .map_or(self.start, |throw| throw.to)
     }
+}
 
-    fn accept_throw(&mut self, throw: &Throw) {
-        self.items.push(throw.worry_level)
-    }
+/// What happened over a [`Simulation`]
+#[derive(Debug, Clone)]
+pub struct Run<W> {
+    /// How many items each monkey inspected
+    pub inspect_counts: Vec<usize>,
+    /// The worry levels of the items each monkey holds at the end, front of the list first
+    pub items: Vec<Vec<W>>,
+    /// Every item's lineage, indexed by item ID. Items are numbered in the order they start
+    /// out: monkey by monkey, front of the list first. Empty unless lineage was asked for.
+    pub lineages: Vec<Lineage<W>>,
+    /// The product of the highest inspection counts, or `None` if it doesn't fit in a `usize`
+    pub monkey_business: Option<usize>,
 }
 
-#[aoc_generator(day11)]
-fn input_generator(input: &str) -> Vec<Monkey> {
-    let result = separated_list1(tag("\n\n"), monkey_whole)(input);
-    result.ok().expect("Bad monkeys").1
+impl<W> Run<W> {
+    /// The monkey holding `item` once `round` is over. `None` for an unknown item.
+    pub fn holder_after(&self, item: usize, round: usize) -> Option<usize> {
+        Some(self.lineages.get(item)?.holder_after(round))
+    }
+
+    /// The throws between monkeys as a Graphviz digraph, with each edge labelled by how many
+    /// throws it carried
+    pub fn to_dot(&self) -> String {
+        let mut edges: BTreeMap<(usize, usize), usize> = BTreeMap::new();
+        for throw in self.lineages.iter().flat_map(|lineage| &lineage.throws) {
+            *edges.entry((throw.from, throw.to)).or_default() += 1;
+        }
+
+        let mut dot = String::from("digraph throws {\n");
+        for id in 0..self.inspect_counts.len() {
+            dot += &format!("    m{id} [label=\"Monkey {id}\"];\n");
+        }
+        for ((from, to), count) in edges {
+            dot += &format!("    m{from} -> m{to} [label=\"{count}\"];\n");
+        }
+        dot += "}\n";
+        dot
+    }
 }
 
-#[aoc(day11, part1)]
-fn part1(input: &Vec<Monkey>) -> usize {
-    let rounds = 20;
-    let mut monkeys = input.clone();
-    let num_monkeys = monkeys.len();
-    let lcm: usize = monkeys
+/// The product of every monkey's divisor. Reducing worry levels modulo this keeps every test
+/// giving the same answer, as long as the operations only add, subtract and multiply.
+fn divisor_product(monkeys: &[Monkey]) -> Result<usize, EvalError> {
+    monkeys
         .iter()
-        .map(|monkey| monkey.test.divisible_by)
-        .product();
-    let disable_worry_division = false;
-
-    for _ in 0..rounds {
-        for m in 0..num_monkeys {
-            let mut throws = vec![];
-            let monkey = &mut monkeys[m];
-
-            while monkey.items.len() > 0 {
-                let throw = monkey.inspect(lcm, disable_worry_division);
-                throws.push(throw);
+        .try_fold(1_usize, |product, monkey| {
+            product.checked_mul(monkey.test.divisible_by)
+        })
+        .ok_or(EvalError::Overflow)
+}
+
+/// How worry levels are kept from growing without bound
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Reduction {
+    /// Worry levels are exact. Only [`BigInt`] can hold them for long: with a fixed size type
+    /// the run soon fails with [`EvalError::Overflow`].
+    None,
+    /// Worry levels are reduced modulo the product of the divisors after every inspection. This
+    /// is exact without relief, but only approximate with it: `(x mod M) / 3` isn't
+    /// `(x / 3) mod M`, so items can be thrown differently from an exact run.
+    Modular,
+    /// As [`Reduction::Modular`], but each item is only followed until its trajectory repeats,
+    /// so any number of rounds takes bounded time. No lineage is recorded, and each monkey's
+    /// final items are listed in order of item ID rather than the order they were thrown. Like
+    /// [`Reduction::Modular`] it's only approximate with relief.
+    FastForward,
+}
+
+/// Some rounds of keep away, set up with [`Simulation::new`] and the builder methods
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Simulation {
+    rounds: usize,
+    relief: usize,
+    reduction: Reduction,
+    top: usize,
+    lineage: bool,
+}
+
+impl Simulation {
+    /// `rounds` rounds with no relief and modular reduction, where monkey business is the
+    /// product of the top two inspection counts
+    pub fn new(rounds: usize) -> Self {
+        Self {
+            rounds,
+            relief: 1,
+            reduction: Reduction::Modular,
+            top: 2,
+            lineage: false,
+        }
+    }
+
+    /// What worry levels are divided by after each inspection, rounding towards zero. 1 means no
+    /// relief.
+    pub fn relief(mut self, divisor: usize) -> Self {
+        self.relief = divisor;
+        self
+    }
+
+    pub fn reduction(mut self, reduction: Reduction) -> Self {
+        self.reduction = reduction;
+        self
+    }
+
+    /// How many of the busiest monkeys' inspection counts go into monkey business
+    pub fn top(mut self, count: usize) -> Self {
+        self.top = count;
+        self
+    }
+
+    /// Whether to record every item's [`Lineage`]
+    pub fn lineage(mut self, record: bool) -> Self {
+        self.lineage = record;
+        self
+    }
+
+    /// Plays the rounds. Fails if an operation or the relief divides by zero, or a worry level
+    /// doesn't fit in `W`.
+    pub fn run<W: Worry>(&self, monkeys: &[Monkey]) -> Result<Run<W>, EvalError> {
+        let mut run = match self.reduction {
+            Reduction::None => self.play(monkeys, None)?,
+            Reduction::Modular => self.play(monkeys, Some(divisor_product(monkeys)?))?,
+            Reduction::FastForward => self.fast_forward(monkeys, divisor_product(monkeys)?)?,
+        };
+
+        let mut counts = run.inspect_counts.clone();
+        counts.sort_by_key(|&count| Reverse(count));
+        run.monkey_business = counts
+            .iter()
+            .take(self.top)
+            .try_fold(1_usize, |product, &count| product.checked_mul(count));
+        Ok(run)
+    }
+
+    /// Plays every round, reducing worry levels modulo `modulus` if there is one
+    fn play<W: Worry>(
+        &self,
+        monkeys: &[Monkey],
+        modulus: Option<usize>,
+    ) -> Result<Run<W>, EvalError> {
+        let mut queues: Vec<Vec<(usize, W)>> = vec![vec![]; monkeys.len()];
+        let mut lineages = vec![];
+        for (id, (queue, monkey)) in queues.iter_mut().zip(monkeys).enumerate() {
+            for &worry_level in &monkey.items {
+                queue.push((lineages.len(), W::from_usize(worry_level)?));
+                lineages.push(Lineage {
+                    start: id,
+                    throws: vec![],
+                });
             }
+        }
+        if !self.lineage {
+            lineages.clear();
+        }
+        let mut inspect_counts = vec![0; monkeys.len()];
 
-            for throw in &mut throws {
-                let monkey_id = throw.to_monkey_id;
-                monkeys[monkey_id].accept_throw(&throw)
+        for round in 1..=self.rounds {
+            for (id, monkey) in monkeys.iter().enumerate() {
+                for (item, old) in std::mem::take(&mut queues[id]) {
+                    let worry_level = monkey.inspect(&old, self.relief, modulus)?;
+                    inspect_counts[id] += 1;
+
+                    let to_monkey_id = monkey.test.apply(&worry_level);
+                    if self.lineage {
+                        lineages[item].throws.push(Throw {
+                            round,
+                            from: id,
+                            to: to_monkey_id,
+                            worry_level: worry_level.clone(),
+                        });
+                    }
+                    queues[to_monkey_id].push((item, worry_level));
+                }
             }
         }
+
+        Ok(Run {
+            inspect_counts,
+            items: queues
+                .into_iter()
+                .map(|queue| {
+                    queue
+                        .into_iter()
+                        .map(|(_, worry_level)| worry_level)
+                        .collect()
+                })
+                .collect(),
+            lineages,
+            monkey_business: None,
+        })
     }
 
-    monkeys.sort_by(|a, b| b.inspect_count.cmp(&a.inspect_count));
-    monkeys.iter().take(2).map(|m| m.inspect_count).product()
-}
+    /// Follows one item through a round, starting with monkey `holder`. It keeps being
+    /// inspected until it's thrown to a monkey that has already had its turn, where it waits
+    /// for the next round. Returns that monkey and the item's worry level.
+    fn play_round(
+        &self,
+        monkeys: &[Monkey],
+        (mut holder, mut worry_level): (usize, isize),
+        modulus: usize,
+        inspect_counts: &mut [usize],
+    ) -> Result<(usize, isize), EvalError> {
+        loop {
+            let monkey = &monkeys[holder];
+            worry_level = monkey.inspect(&worry_level, self.relief, Some(modulus))?;
+            inspect_counts[holder] += 1;
 
-#[aoc(day11, part2)]
-fn part2(input: &Vec<Monkey>) -> usize {
-    let rounds = 10000;
-    let mut monkeys = input.clone();
-    let lcm: usize = monkeys
-        .iter()
-        .map(|monkey| monkey.test.divisible_by)
-        .product();
-    let num_monkeys = monkeys.len();
-    let disable_worry_division = true;
-
-    for _ in 0..rounds {
-        for m in 0..num_monkeys {
-            let mut throws = vec![];
-            let monkey = &mut monkeys[m];
-
-            while monkey.items.len() > 0 {
-                let throw = monkey.inspect(lcm, disable_worry_division);
-                throws.push(throw);
+            let to_monkey_id = monkey.test.apply(&worry_level);
+            if to_monkey_id <= holder {
+                return Ok((to_monkey_id, worry_level));
             }
+            holder = to_monkey_id;
+        }
+    }
 
-            for throw in &mut throws {
-                let monkey_id = throw.to_monkey_id;
-                monkeys[monkey_id].accept_throw(&throw)
+    /// How many times each monkey inspects one item, and the item's holder and worry level at
+    /// the end. Items never affect each other, and once worry levels are reduced an item can
+    /// only be in finitely many states, so its trajectory eventually repeats. Once it does the
+    /// rest is worked out without playing it.
+    fn follow_item(
+        &self,
+        monkeys: &[Monkey],
+        start: (usize, isize),
+        modulus: usize,
+    ) -> Result<(Vec<usize>, (usize, isize)), EvalError> {
+        // The round each state was first seen at the start of
+        let mut seen: HashMap<(usize, isize), usize> = HashMap::new();
+        // The state at the start of each round, and inspections by each monkey before it
+        let mut states = vec![start];
+        let mut totals: Vec<Vec<usize>> = vec![vec![0; monkeys.len()]];
+
+        for round in 0..self.rounds {
+            if let Some(&first) = seen.get(&states[round]) {
+                let period = round - first;
+                let remaining = self.rounds - round;
+                let (cycles, remainder) = (remaining / period, remaining % period);
+                let counts = (0..monkeys.len())
+                    .map(|id| {
+                        let per_cycle = totals[round][id] - totals[first][id];
+                        let partial = totals[first + remainder][id] - totals[first][id];
+                        totals[round][id] + cycles * per_cycle + partial
+                    })
+                    .collect();
+                return Ok((counts, states[first + remainder]));
             }
+            seen.insert(states[round], round);
+
+            let mut counts = totals[round].clone();
+            states.push(self.play_round(monkeys, states[round], modulus, &mut counts)?);
+            totals.push(counts);
         }
+
+        Ok((totals.swap_remove(self.rounds), states[self.rounds]))
     }
 
-    monkeys.sort_by(|a, b| b.inspect_count.cmp(&a.inspect_count));
-    monkeys.iter().take(2).map(|m| m.inspect_count).product()
+    fn fast_forward<W: Worry>(
+        &self,
+        monkeys: &[Monkey],
+        modulus: usize,
+    ) -> Result<Run<W>, EvalError> {
+        let mut inspect_counts = vec![0; monkeys.len()];
+        let mut items = vec![vec![]; monkeys.len()];
+        for (id, monkey) in monkeys.iter().enumerate() {
+            for &worry_level in &monkey.items {
+                let start = (id, isize::from_usize(worry_level)?);
+                let (counts, (holder, worry_level)) = self.follow_item(monkeys, start, modulus)?;
+                for (total, count) in inspect_counts.iter_mut().zip(counts) {
+                    *total += count;
+                }
+                items[holder].push(W::from_usize(worry_level.unsigned_abs())?);
+            }
+        }
+
+        Ok(Run {
+            inspect_counts,
+            items,
+            lineages: vec![],
+            monkey_business: None,
+        })
+    }
+}
+
+/// The first point where reducing worry levels made an item's worry level disagree with the
+/// exact one, modulo the product of the divisors
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Divergence {
+    pub item: usize,
+    /// How many inspections of `item` came before this one
+    pub inspection: usize,
+    pub round: usize,
+    /// The monkey that inspected it
+    pub monkey: usize,
+    pub exact: BigInt,
+    pub modular: isize,
+}
+
+/// Runs `simulation` with and without modular reduction and finds the earliest inspection where
+/// they disagree: the earliest round, then the first monkey to take its turn in it, picking the
+/// lowest item ID if that monkey saw more than one. `None` means reduction made no difference,
+/// which is guaranteed when the operations only add, subtract and multiply and there's no
+/// relief; any division can break it.
+pub fn find_divergence(
+    monkeys: &[Monkey],
+    simulation: Simulation,
+) -> Result<Option<Divergence>, EvalError> {
+    let modulus = divisor_product(monkeys)?;
+    let simulation = simulation.lineage(true);
+    let exact: Run<BigInt> = simulation.reduction(Reduction::None).run(monkeys)?;
+    let modular: Run<isize> = simulation.reduction(Reduction::Modular).run(monkeys)?;
+
+    let divergence = exact
+        .lineages
+        .iter()
+        .zip(&modular.lineages)
+        .enumerate()
+        .flat_map(|(item, (exact, modular))| {
+            exact
+                .throws
+                .iter()
+                .zip(&modular.throws)
+                .enumerate()
+                .map(move |(inspection, throws)| (item, inspection, throws))
+        })
+        .filter(|(_, _, (exact, modular))| {
+            exact.worry_level.remainder(modulus) != modular.worry_level.unsigned_abs()
+        })
+        .min_by_key(|&(item, _, (exact, _))| (exact.round, exact.from, item))
+        .map(|(item, inspection, (exact, modular))| Divergence {
+            item,
+            inspection,
+            round: exact.round,
+            monkey: exact.from,
+            exact: exact.worry_level.clone(),
+            modular: modular.worry_level,
+        });
+
+    Ok(divergence)
+}
+
+/// Every monkey has to throw to one that exists, which can only be checked once they're all
+/// parsed
+fn check_throw_targets(input: &str, monkey_count: usize) -> Result<(), ParseError> {
+    const THROW: &str = "throw to monkey ";
+    for (index, line) in input.lines().enumerate() {
+        let Some((before, target)) = line.split_once(THROW) else {
+            continue;
+        };
+        if target
+            .parse::<usize>()
+            .is_ok_and(|target| target >= monkey_count)
+        {
+            let column = before.chars().count() + THROW.len() + 1;
+            return Err(ParseError::new(11, index + 1, column, line));
+        }
+    }
+    Ok(())
+}
+
+#[aoc_generator(day11)]
+fn input_generator(input: &str) -> Result<Vec<Monkey>, ParseError> {
+    let monkeys = finish(11, input, blocks_of(monkey_whole))?;
+    check_throw_targets(input, monkeys.len())?;
+    Ok(monkeys)
+}
+
+#[aoc(day11, part1)]
+fn part1(input: &[Monkey]) -> usize {
+    // Relief doesn't survive modular reduction, so this has to be exact
+    Simulation::new(20)
+        .relief(3)
+        .reduction(Reduction::None)
+        .run::<BigInt>(input)
+        .expect("only dividing by zero can go wrong with exact worry levels")
+        .monkey_business
+        .expect("monkey business fits in a usize")
+}
+
+#[aoc(day11, part2)]
+fn part2(input: &[Monkey]) -> usize {
+    Simulation::new(10000)
+        .reduction(Reduction::FastForward)
+        .run::<isize>(input)
+        .expect("worry levels fit in an isize and nothing divides by zero")
+        .monkey_business
+        .expect("monkey business fits in a usize")
+}
+
+pub struct Day11;
+
+impl Solution for Day11 {
+    type Input = Vec<Monkey>;
+    type Answer1 = usize;
+    type Answer2 = usize;
+
+    fn parse(input: &str) -> Result<Self::Input, ParseError> {
+        input_generator(input)
+    }
+
+    fn part1(input: &Self::Input) -> Self::Answer1 {
+        part1(input)
+    }
+
+    fn part2(input: &Self::Input) -> Self::Answer2 {
+        part2(input)
+    }
 }
 
 #[cfg(test)]
@@ -284,13 +837,316 @@ Monkey 3:
 
     #[test]
     fn part1_test() {
-        let input = input_generator(INPUT_STR);
+        let input = input_generator(INPUT_STR).unwrap();
         assert_eq!(part1(&input), 10605);
     }
 
+    #[test]
+    fn part1_exact_test() {
+        // With divisors this small, worry levels pass their product within a few inspections
+        let input = INPUT_STR
+            .replace("divisible by 23", "divisible by 2")
+            .replace("divisible by 19", "divisible by 3")
+            .replace("divisible by 13", "divisible by 5")
+            .replace("divisible by 17", "divisible by 7");
+        let monkeys = input_generator(&input).unwrap();
+        assert_eq!(part1(&monkeys), 11400);
+
+        let approximate: Run<isize> = Simulation::new(20).relief(3).run(&monkeys).unwrap();
+        assert_eq!(approximate.monkey_business, Some(9568));
+    }
+
     #[test]
     fn part2_test() {
-        let input = input_generator(INPUT_STR);
+        let input = input_generator(INPUT_STR).unwrap();
         assert_eq!(part2(&input), 2713310158);
     }
+
+    #[test]
+    fn input_generator_error_test() {
+        let input = INPUT_STR.replace("divisible by 19", "divisible by x");
+        let error = input_generator(&input).err().unwrap();
+        assert_eq!(error, ParseError::new(11, 11, 22, "  Test: divisible by x"));
+
+        let zero = INPUT_STR.replace("divisible by 13", "divisible by 0");
+        let error = input_generator(&zero).err().unwrap();
+        assert_eq!(error, ParseError::new(11, 18, 22, "  Test: divisible by 0"));
+
+        let missing = INPUT_STR.replacen("throw to monkey 1", "throw to monkey 7", 1);
+        let error = input_generator(&missing).err().unwrap();
+        assert_eq!(
+            error,
+            ParseError::new(11, 19, 30, "    If true: throw to monkey 7")
+        );
+    }
+
+    #[test]
+    fn expression_test() {
+        let expr = Expr::parse("(old + 3) * 2 - old / (1 + 1)").unwrap();
+        assert_eq!(expr.to_string(), "((old + 3) * 2) - (old / (1 + 1))");
+        assert_eq!(expr.variables(), vec!["old", "old"]);
+
+        let old = |value: isize| move |name: &str| (name == "old").then_some(value);
+        assert_eq!(expr.evaluate(&old(10)), Ok(21));
+        assert_eq!(Expr::parse("2 - old").unwrap().evaluate(&old(3)), Ok(-1));
+        assert_eq!(
+            Expr::parse("(2 - old) / 2").unwrap().evaluate(&old(9)),
+            Ok(-3)
+        );
+        assert_eq!(
+            Expr::parse("old / 0").unwrap().evaluate(&old(3)),
+            Err(EvalError::DivisionByZero)
+        );
+        assert_eq!(
+            Expr::parse("new").unwrap().evaluate(&old(3)),
+            Err(EvalError::UnboundVariable(String::from("new")))
+        );
+        assert!(Expr::parse("old +").is_err());
+    }
+
+    #[test]
+    fn any_operation_test() {
+        let input = INPUT_STR
+            .replace("new = old * 19", "new = old + old")
+            .replace("new = old + 6", "new = (old - 50) * 3");
+        let monkeys = input_generator(&input).unwrap();
+        assert_eq!(monkeys[0].operation.apply(&4_isize), Ok(8));
+        assert_eq!(monkeys[1].operation.apply(&54_isize), Ok(12));
+
+        let unknown = INPUT_STR.replace("new = old * 19", "new = cow * 19");
+        assert_eq!(input_generator(&unknown).err().unwrap().line, 3);
+    }
+
+    #[test]
+    fn exact_worry_test() {
+        let monkeys = input_generator(INPUT_STR).unwrap();
+        let exact: Run<BigInt> = Simulation::new(20)
+            .relief(3)
+            .reduction(Reduction::None)
+            .lineage(true)
+            .run(&monkeys)
+            .unwrap();
+        assert_eq!(exact.monkey_business, Some(10605));
+        assert_eq!(exact.lineages.len(), 10);
+        assert_eq!(exact.lineages[0].throws[0].worry_level, BigInt::from(500));
+
+        // Adding and multiplying commute with reduction, so without relief the runs agree
+        let simulation = Simulation::new(8);
+        assert_eq!(find_divergence(&monkeys, simulation), Ok(None));
+        assert_eq!(
+            simulation
+                .reduction(Reduction::None)
+                .run::<BigInt>(&monkeys)
+                .unwrap()
+                .inspect_counts,
+            simulation.run::<isize>(&monkeys).unwrap().inspect_counts
+        );
+    }
+
+    #[test]
+    fn divergence_test() {
+        // Halving an odd number rounds down, which reduced worry levels don't do in step
+        let halving = INPUT_STR.replace("new = old + 6", "new = old * 7 / 2");
+        let monkeys = input_generator(&halving).unwrap();
+        let divergence = find_divergence(&monkeys, Simulation::new(8))
+            .unwrap()
+            .unwrap();
+        let modulus = divisor_product(&monkeys).unwrap();
+        assert_ne!(
+            divergence.exact.remainder(modulus),
+            divergence.modular.unsigned_abs()
+        );
+
+        // Nothing diverged any earlier in time, whichever item it happened to
+        let simulation = Simulation::new(8).lineage(true);
+        let exact: Run<BigInt> = simulation.reduction(Reduction::None).run(&monkeys).unwrap();
+        let modular: Run<isize> = simulation.run(&monkeys).unwrap();
+        let earliest = exact
+            .lineages
+            .iter()
+            .zip(&modular.lineages)
+            .flat_map(|(exact, modular)| exact.throws.iter().zip(&modular.throws))
+            .filter(|(exact, modular)| {
+                exact.worry_level.remainder(modulus) != modular.worry_level.unsigned_abs()
+            })
+            .map(|(exact, _)| (exact.round, exact.from))
+            .min();
+        assert_eq!(earliest, Some((divergence.round, divergence.monkey)));
+    }
+
+    #[test]
+    fn lineage_test() {
+        let monkeys = input_generator(INPUT_STR).unwrap();
+        let run: Run<isize> = Simulation::new(20)
+            .relief(3)
+            .lineage(true)
+            .run(&monkeys)
+            .unwrap();
+
+        // The first item goes 0 -> 3 -> 1 in the first round, ending up as 167
+        let first = &run.lineages[0];
+        assert_eq!(first.start, 0);
+        assert_eq!(
+            first.throws[..2],
+            [
+                Throw {
+                    round: 1,
+                    from: 0,
+                    to: 3,
+                    worry_level: 500
+                },
+                Throw {
+                    round: 1,
+                    from: 3,
+                    to: 1,
+                    worry_level: 167
+                },
+            ]
+        );
+        assert_eq!(run.holder_after(0, 0), Some(0));
+        assert_eq!(run.holder_after(0, 1), Some(1));
+        assert_eq!(run.holder_after(10, 1), None);
+
+        // Every inspection is exactly one throw
+        let throws: usize = run.lineages.iter().map(|l| l.throws.len()).sum();
+        assert_eq!(throws, run.inspect_counts.iter().sum::<usize>());
+        for lineage in &run.lineages {
+            assert!(lineage.throws.windows(2).all(|w| w[0].to == w[1].from));
+        }
+    }
+
+    #[test]
+    fn to_dot_test() {
+        let monkeys = input_generator(INPUT_STR).unwrap();
+        let dot = Simulation::new(1)
+            .relief(3)
+            .lineage(true)
+            .run::<isize>(&monkeys)
+            .unwrap()
+            .to_dot();
+        assert!(dot.starts_with("digraph throws {\n    m0 [label=\"Monkey 0\"];\n"));
+        assert!(dot.contains("    m0 -> m3 [label=\"2\"];\n"));
+        assert!(dot.ends_with("}\n"));
+    }
+
+    #[test]
+    fn fast_forward_test() {
+        let monkeys = input_generator(INPUT_STR).unwrap();
+        // Fast forwarding has to agree with playing every round, even where relief makes both
+        // of them approximate
+        for relief in [1, 3] {
+            for rounds in [0, 1, 20, 1000, 2345] {
+                let simulation = Simulation::new(rounds).relief(relief);
+                let played: Run<isize> = simulation.run(&monkeys).unwrap();
+                let mut skipped: Run<isize> = simulation
+                    .reduction(Reduction::FastForward)
+                    .run(&monkeys)
+                    .unwrap();
+                assert_eq!(
+                    skipped.inspect_counts, played.inspect_counts,
+                    "{rounds} rounds, relief {relief}"
+                );
+
+                let mut played_items = played.items;
+                for items in played_items.iter_mut().chain(&mut skipped.items) {
+                    items.sort();
+                }
+                assert_eq!(skipped.items, played_items);
+            }
+        }
+
+        // Worry levels that go below zero still repeat once they're reduced
+        let subtraction = INPUT_STR.replace("new = old + 3", "new = 100 - old");
+        let negative = input_generator(&subtraction).unwrap();
+        let simulation = Simulation::new(10000);
+        let played: Run<isize> = simulation.run(&negative).unwrap();
+        let skipped: Run<isize> = simulation
+            .reduction(Reduction::FastForward)
+            .run(&negative)
+            .unwrap();
+        assert_eq!(played.inspect_counts, skipped.inspect_counts);
+
+        // Every item is inspected at least once a round and at most once by each monkey
+        let rounds = 1_000_000_000_000;
+        let run: Run<isize> = Simulation::new(rounds)
+            .reduction(Reduction::FastForward)
+            .run(&monkeys)
+            .unwrap();
+        assert_eq!(run.monkey_business, None);
+        let total: usize = run.inspect_counts.iter().sum();
+        assert!((10 * rounds..=40 * rounds).contains(&total));
+    }
+
+    #[test]
+    fn simulation_test() {
+        let monkeys = input_generator(INPUT_STR).unwrap();
+        let run: Run<isize> = Simulation::new(20).relief(3).top(3).run(&monkeys).unwrap();
+        assert_eq!(run.inspect_counts, [101, 95, 7, 105]);
+        assert_eq!(run.monkey_business, Some(105 * 101 * 95));
+        assert_eq!(
+            run.items,
+            [
+                vec![10, 12, 14, 26, 34],
+                vec![245, 93, 53, 199, 115],
+                vec![],
+                vec![]
+            ]
+        );
+        assert!(run.lineages.is_empty());
+    }
+
+    #[test]
+    fn eval_error_test() {
+        let constant = INPUT_STR.replace("new = old + 6", "new = old + 9999999999999999999");
+        let monkeys = input_generator(&constant).unwrap();
+        let simulation = Simulation::new(1);
+        assert_eq!(
+            simulation.run::<isize>(&monkeys).err(),
+            Some(EvalError::Overflow)
+        );
+
+        let item = INPUT_STR.replace("Starting items: 74", "Starting items: 9999999999999999999");
+        let monkeys = input_generator(&item).unwrap();
+        assert_eq!(
+            simulation.run::<isize>(&monkeys).err(),
+            Some(EvalError::Overflow)
+        );
+        let exact = simulation.reduction(Reduction::None);
+        assert!(exact.run::<BigInt>(&monkeys).is_ok());
+
+        let division = INPUT_STR.replace("new = old + 6", "new = old / (old - old)");
+        let monkeys = input_generator(&division).unwrap();
+        assert_eq!(
+            simulation.run::<isize>(&monkeys).err(),
+            Some(EvalError::DivisionByZero)
+        );
+
+        let monkeys = input_generator(INPUT_STR).unwrap();
+        assert_eq!(
+            simulation.relief(0).run::<isize>(&monkeys).err(),
+            Some(EvalError::DivisionByZero)
+        );
+
+        // Subtraction can take worry levels below zero, which is fine
+        let subtraction = INPUT_STR.replace("new = old + 3", "new = 100 - old");
+        let monkeys = input_generator(&subtraction).unwrap();
+        assert_eq!(find_divergence(&monkeys, Simulation::new(8)), Ok(None));
+        let exact: Run<BigInt> = Simulation::new(8)
+            .reduction(Reduction::None)
+            .run(&monkeys)
+            .unwrap();
+        assert!(exact
+            .items
+            .iter()
+            .flatten()
+            .any(|worry| *worry < BigInt::ZERO));
+
+        // Exact worry levels soon outgrow an `isize`
+        let monkeys = input_generator(INPUT_STR).unwrap();
+        let exact = Simulation::new(10000).reduction(Reduction::None);
+        assert_eq!(
+            exact.run::<isize>(&monkeys).err(),
+            Some(EvalError::Overflow)
+        );
+    }
 }
@@ -1,50 +1,107 @@
-use std::collections::HashMap;
-
-use nom::{
-    character::complete::{char, none_of},
-    multi::{many1, separated_list1},
-    IResult,
-};
-
-fn find_start(locations: &[Vec<Location>]) -> Point {
-    let len = locations[0].len();
-
-    for y in 0..len {
-        for x in 0..len {
-            let loc = &locations[y][x];
-            if let Location::Start(_) = loc {
-                return Point { x, y };
-            }
-        }
-    }
+use std::error::Error;
+use std::fmt::Display;
+use std::str::FromStr;
+
+use crate::error::ParseError;
+use crate::grid::{Grid, GridError};
+use crate::search::{self, GridSearch};
+use crate::solution::Solution;
+use crate::vec2::Vec2;
+
+/// Something wrong with a heightmap. Positions are stored as `(x, y)` from the top left, starting
+/// at 0 like any other [`Grid`] position. Messages count lines and columns from 1 instead, to match
+/// [`ParseError`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum MapError {
+    Empty,
+    RaggedRow {
+        y: usize,
+        expected: usize,
+        found: usize,
+    },
+    InvalidHeight {
+        at: Vec2,
+        found: char,
+    },
+    MissingStart,
+    MissingEnd,
+    DuplicateStart(Vec2, Vec2),
+    DuplicateEnd(Vec2, Vec2),
+}
 
-    unreachable!("Couldn't find the start")
+impl MapError {
+    /// The same error as a [`ParseError`] pointing into `input`
+    pub fn to_parse_error(&self, input: &str) -> ParseError {
+        let (x, y) = match self {
+            MapError::Empty | MapError::MissingStart | MapError::MissingEnd => (0, 0),
+            MapError::RaggedRow { y, expected, found } => (*expected.min(found), *y),
+            MapError::InvalidHeight { at, .. }
+            | MapError::DuplicateStart(_, at)
+            | MapError::DuplicateEnd(_, at) => at.to_indices().unwrap_or((0, 0)),
+        };
+        let snippet = input.lines().nth(y).unwrap_or("");
+
+        ParseError::new(12, y + 1, x + 1, snippet)
+    }
 }
 
-fn find_end(locations: &[Vec<Location>]) -> Point {
-    let y_max = locations.len();
-    let x_max = locations[0].len();
+/// `position` as a line and column counted from 1
+fn line_and_column(position: &Vec2) -> String {
+    format!("line {}, column {}", position.y + 1, position.x + 1)
+}
 
-    for y in 0..y_max {
-        for x in 0..x_max {
-            let loc = &locations[y][x];
-            if let Location::End(_) = loc {
-                return Point { x, y };
+impl Display for MapError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            MapError::Empty => write!(f, "the map is empty"),
+            MapError::RaggedRow { y, expected, found } => {
+                write!(f, "line {} has {found} cells, expected {expected}", y + 1)
             }
+            MapError::InvalidHeight { at, found } => {
+                write!(
+                    f,
+                    "invalid height {found:?} at {}, expected a to z, S or E",
+                    line_and_column(at)
+                )
+            }
+            MapError::MissingStart => write!(f, "there's no start (S)"),
+            MapError::MissingEnd => write!(f, "there's no end (E)"),
+            MapError::DuplicateStart(first, second) => write!(
+                f,
+                "there are two starts (S), at {} and {}",
+                line_and_column(first),
+                line_and_column(second)
+            ),
+            MapError::DuplicateEnd(first, second) => write!(
+                f,
+                "there are two ends (E), at {} and {}",
+                line_and_column(first),
+                line_and_column(second)
+            ),
         }
     }
-
-    unreachable!("Couldn't find the end")
 }
 
-#[derive(Debug, PartialEq, Eq, Hash, Clone)]
-struct Point {
-    x: usize,
-    y: usize,
+impl Error for MapError {}
+
+/// The only position matching `is_wanted`, or the first two if there's more than one
+fn find_unique(
+    locations: &Grid<Location>,
+    is_wanted: impl Fn(&Location) -> bool,
+) -> Result<Option<Vec2>, (Vec2, Vec2)> {
+    let mut found = locations
+        .iter()
+        .filter(|(_, location)| is_wanted(location))
+        .map(|(position, _)| position);
+
+    match (found.next(), found.next()) {
+        (Some(first), Some(second)) => Err((first, second)),
+        (first, _) => Ok(first),
+    }
 }
 
 #[derive(Debug)]
-enum Location {
+pub enum Location {
     Start(char),
     End(char),
     // Locations that are neither start nor end
@@ -52,11 +109,12 @@ enum Location {
 }
 
 impl Location {
-    fn new(c: char) -> Self {
+    fn new(c: char) -> Option<Self> {
         match c {
-            'S' => Self::Start('a'),
-            'E' => Self::End('z'),
-            c => Self::Pos(c),
+            'S' => Some(Self::Start('a')),
+            'E' => Some(Self::End('z')),
+            'a'..='z' => Some(Self::Pos(c)),
+            _ => None,
         }
     }
 
@@ -69,252 +127,391 @@ impl Location {
     }
 }
 
-struct Map {
-    map: Vec<Vec<usize>>,
-    width: usize,
-    height: usize,
-    start: Point,
-    end: Point,
-    distance_from_start: HashMap<Point, usize>,
+/// Side length of each cell in [`Map::to_svg`], in pixels
+const SVG_CELL_SIZE: isize = 10;
+
+/// The arrow pointing from one cell to the next along a path
+fn arrow(step: Vec2) -> char {
+    match step {
+        Vec2::UP => '^',
+        Vec2::DOWN => 'v',
+        Vec2::LEFT => '<',
+        Vec2::RIGHT => '>',
+        _ => '?',
+    }
 }
 
-impl Map {
-    fn new(input: &Vec<Vec<Location>>, ascend: bool) -> Self {
-        let width = input[0].len();
-        let height = input.len();
-        let start = find_start(&input);
-        let end = find_end(&input);
-        let map = input
-            .iter()
-            .map(|inner| {
-                inner
-                    .iter()
-                    .map(|l| l.char() as usize - 'a' as usize)
-                    .collect()
-            })
-            .collect();
+/// Greens for the valleys through to pale browns at the peaks
+fn height_colour(height: usize) -> String {
+    let hue = 120 - height * 90 / 25;
+    let lightness = 25 + height * 2;
+    format!("hsl({hue}, 45%, {lightness}%)")
+}
 
-        let mut distance_from_start = HashMap::new();
-        if ascend {
-            distance_from_start.insert(start.clone(), 0);
-        } else {
-            distance_from_start.insert(end.clone(), 0);
-        }
+/// A distance field as text, one right-aligned column per cell with `.` where there's no distance
+pub fn render_distance_field(field: &Grid<Option<usize>>) -> String {
+    let cell_width = field
+        .iter()
+        .filter_map(|(_, distance)| *distance)
+        .max()
+        .map_or(1, |max| max.to_string().len());
+
+    field
+        .rows()
+        .map(|row| {
+            row.iter()
+                .map(|distance| match distance {
+                    Some(distance) => format!("{distance:>cell_width$}"),
+                    None => format!("{:>cell_width$}", "."),
+                })
+                .collect::<Vec<_>>()
+                .join(" ")
+        })
+        .collect::<Vec<_>>()
+        .join("\n")
+}
 
-        Self {
-            map,
-            width,
-            height,
-            start,
-            end,
-            distance_from_start,
-        }
+/// Which steps between neighbouring cells are allowed, and what they cost
+#[derive(Debug, Clone, Copy)]
+pub struct ClimbingRules {
+    /// The most a single step can climb
+    pub max_climb: usize,
+    /// The most a single step can drop, or `None` for any distance
+    pub max_drop: Option<usize>,
+    /// Whether steps can be taken diagonally as well as orthogonally
+    pub diagonal: bool,
+    /// The cost of a step, given the change in height (positive when climbing)
+    pub step_cost: fn(isize) -> usize,
+}
+
+impl ClimbingRules {
+    /// The puzzle's rules: climb at most one, drop any distance, every step costs 1
+    pub const PUZZLE: Self = Self {
+        max_climb: 1,
+        max_drop: None,
+        diagonal: false,
+        step_cost: |_| 1,
+    };
+
+    /// The cost of stepping between the two heights, or `None` if the step isn't allowed
+    fn step(&self, from: usize, to: usize) -> Option<usize> {
+        let climb_ok = to <= from + self.max_climb;
+        let drop_ok = self.max_drop.is_none_or(|max_drop| from <= to + max_drop);
+
+        (climb_ok && drop_ok).then(|| (self.step_cost)(to as isize - from as isize))
     }
+}
 
-    fn traverse(&mut self, points: &[Point], ascend: bool) {
-        let mut new_points: Vec<Point> = Vec::new();
+impl Default for ClimbingRules {
+    fn default() -> Self {
+        Self::PUZZLE
+    }
+}
 
-        for point in points {
-            let distance = self
-                .distance_from_start
-                .get(point)
-                .expect("No distance found")
-                + 1;
+/// Picks out cells of a [`Map`] for a query
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Selector {
+    Start,
+    End,
+    /// Every cell of this height, `'a'` to `'z'`. The start counts as `'a'` and the end as `'z'`.
+    Height(char),
+    Point(Vec2),
+}
 
-            let directions = self.spread(point, ascend);
-            for dir in directions {
-                if !self.distance_from_start.contains_key(&dir) {
-                    self.distance_from_start.insert(dir.clone(), distance);
-                    new_points.push(dir);
+impl FromStr for Selector {
+    type Err = String;
+
+    /// `S`, `E`, a height letter, or `x,y`
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let error = || format!("invalid cell '{s}', expected S, E, a to z, or x,y");
+
+        match s {
+            "S" => Ok(Self::Start),
+            "E" => Ok(Self::End),
+            _ => match s.split_once(',') {
+                Some((x, y)) => {
+                    let x = x.trim().parse().map_err(|_| error())?;
+                    let y = y.trim().parse().map_err(|_| error())?;
+                    Ok(Self::Point(Vec2::new(x, y)))
                 }
-            }
-        }
-        if new_points.len() != 0 {
-            self.traverse(&new_points, ascend)
+                None => match s.chars().collect::<Vec<_>>()[..] {
+                    [c] if c.is_ascii_lowercase() => Ok(Self::Height(c)),
+                    _ => Err(error()),
+                },
+            },
         }
     }
+}
 
-    fn height_at(&self, x: usize, y: usize) -> usize {
-        self.map[y][x]
+pub struct Map {
+    map: Grid<usize>,
+    pub start: Vec2,
+    pub end: Vec2,
+}
+
+impl Map {
+    /// Reads a heightmap, checking it's rectangular, made only of heights, and has exactly one
+    /// start and one end
+    pub fn parse(input: &str) -> Result<Self, MapError> {
+        let rows = input
+            .lines()
+            .enumerate()
+            .map(|(y, line)| {
+                line.chars()
+                    .enumerate()
+                    .map(|(x, c)| {
+                        Location::new(c).ok_or(MapError::InvalidHeight {
+                            at: Vec2::from_indices(x, y),
+                            found: c,
+                        })
+                    })
+                    .collect::<Result<Vec<_>, _>>()
+            })
+            .collect::<Result<Vec<_>, _>>()?;
+
+        let locations = Grid::from_rows(rows).map_err(|e| match e {
+            GridError::Empty => MapError::Empty,
+            GridError::RaggedRow {
+                row,
+                expected,
+                found,
+            } => MapError::RaggedRow {
+                y: row,
+                expected,
+                found,
+            },
+        })?;
+
+        let start = find_unique(&locations, |l| matches!(l, Location::Start(_)))
+            .map_err(|(first, second)| MapError::DuplicateStart(first, second))?
+            .ok_or(MapError::MissingStart)?;
+        let end = find_unique(&locations, |l| matches!(l, Location::End(_)))
+            .map_err(|(first, second)| MapError::DuplicateEnd(first, second))?
+            .ok_or(MapError::MissingEnd)?;
+        let map = locations.map(|l| l.char() as usize - 'a' as usize);
+
+        Ok(Self { map, start, end })
     }
 
-    fn can_ascend(&self, current_point: &Point, to: &Point) -> bool {
-        let height = self.height_at(current_point.x, current_point.y);
-        let height_to = self.height_at(to.x, to.y);
+    fn neighbors(&self, point: Vec2, diagonal: bool) -> impl Iterator<Item = Vec2> + '_ {
+        let directions: &[Vec2] = if diagonal {
+            &Vec2::DIRECTIONS_8
+        } else {
+            &Vec2::DIRECTIONS_4
+        };
 
-        height + 1 == height_to || height >= height_to
+        directions
+            .iter()
+            .map(move |direction| point + *direction)
+            .filter(|neighbor| self.map.contains(*neighbor))
     }
 
-    fn can_descend(&self, current_point: &Point, to: &Point) -> bool {
-        let height = self.height_at(current_point.x, current_point.y);
-        let height_to = self.height_at(to.x, to.y);
+    /// The cells that can be stepped to from `from`, with the cost of each step
+    fn steps_from(
+        &self,
+        from: Vec2,
+        rules: ClimbingRules,
+    ) -> impl Iterator<Item = (Vec2, usize)> + '_ {
+        self.neighbors(from, rules.diagonal).filter_map(move |to| {
+            let cost = rules.step(self.height_at(from), self.height_at(to))?;
+            Some((to, cost))
+        })
+    }
 
-        let can = height == height_to || height_to > height || height - 1 == height_to;
-        // println!("{can}");
-        can
+    /// The cells that can step to `to`, with the cost of each step
+    fn steps_to(&self, to: Vec2, rules: ClimbingRules) -> impl Iterator<Item = (Vec2, usize)> + '_ {
+        self.neighbors(to, rules.diagonal).filter_map(move |from| {
+            let cost = rules.step(self.height_at(from), self.height_at(to))?;
+            Some((from, cost))
+        })
     }
 
-    fn spread(&self, current_point: &Point, ascend: bool) -> Vec<Point> {
-        let up = self.up(current_point, ascend);
-        let down = self.down(current_point, ascend);
-        let left = self.left(current_point, ascend);
-        let right = self.right(current_point, ascend);
+    /// The cheapest way from `from` to every cell it can reach
+    pub fn search_from(
+        &self,
+        sources: impl IntoIterator<Item = Vec2>,
+        rules: &ClimbingRules,
+    ) -> GridSearch {
+        search::grid_dijkstra(&self.map, sources, |point| self.steps_from(point, *rules))
+    }
 
-        let directions = vec![up, down, left, right];
-        directions.into_iter().filter_map(|x| x).collect()
+    /// The cheapest way to any of `targets` from every cell that can reach one.
+    /// Predecessors point towards the targets.
+    pub fn search_to(
+        &self,
+        targets: impl IntoIterator<Item = Vec2>,
+        rules: &ClimbingRules,
+    ) -> GridSearch {
+        search::grid_dijkstra(&self.map, targets, |point| self.steps_to(point, *rules))
     }
 
-    fn up(&self, current_point: &Point, ascend: bool) -> Option<Point> {
-        if current_point.y == 0 {
-            return None;
-        }
+    /// Every cell matching any of `selectors`, row by row. Points outside the map are ignored.
+    pub fn select(&self, selectors: &[Selector]) -> Vec<Vec2> {
+        self.map
+            .iter()
+            .filter(|(point, height)| {
+                selectors.iter().any(|selector| match selector {
+                    Selector::Start => *point == self.start,
+                    Selector::End => *point == self.end,
+                    // Anything outside 'a' to 'z' matches no height
+                    Selector::Height(c) => {
+                        (*c as usize).checked_sub('a' as usize) == Some(**height)
+                    }
+                    Selector::Point(p) => point == p,
+                })
+            })
+            .map(|(point, _)| point)
+            .collect()
+    }
 
-        let to = Point {
-            x: current_point.x,
-            y: current_point.y - 1,
-        };
+    /// The cheapest route from any of `sources` to any of `targets`
+    pub fn shortest_distance(
+        &self,
+        sources: &[Vec2],
+        targets: &[Vec2],
+        rules: &ClimbingRules,
+    ) -> Option<usize> {
+        let search = self.search_from(sources.iter().copied(), rules);
+        targets
+            .iter()
+            .filter_map(|target| search.distance(target))
+            .min()
+    }
 
-        if ascend {
-            if self.can_ascend(current_point, &to) {
-                Some(to)
-            } else {
-                None
-            }
-        } else {
-            if self.can_descend(current_point, &to) {
-                Some(to)
-            } else {
-                None
-            }
-        }
+    /// The cost of getting to every cell from the nearest of `sources`, or `None` where it can't
+    /// be reached
+    pub fn distance_field(&self, sources: &[Vec2], rules: &ClimbingRules) -> Grid<Option<usize>> {
+        self.search_from(sources.iter().copied(), rules).distances
     }
 
-    fn down(&self, current_point: &Point, ascend: bool) -> Option<Point> {
-        if current_point.y >= self.height - 1 {
-            return None;
-        }
-        let to = Point {
-            x: current_point.x,
-            y: current_point.y + 1,
-        };
+    /// Every cell that can't be reached from any of `sources`, row by row
+    pub fn unreachable(&self, sources: &[Vec2], rules: &ClimbingRules) -> Vec<Vec2> {
+        self.distance_field(sources, rules)
+            .iter()
+            .filter(|(_, distance)| distance.is_none())
+            .map(|(point, _)| point)
+            .collect()
+    }
 
-        if ascend {
-            if self.can_ascend(current_point, &to) {
-                Some(to)
-            } else {
-                None
-            }
-        } else {
-            if self.can_descend(current_point, &to) {
-                Some(to)
-            } else {
-                None
-            }
-        }
+    /// The cost of the cheapest route from the start to the end, and the route itself
+    /// including both ends, if there is one
+    pub fn shortest_path(&self, rules: &ClimbingRules) -> Option<(usize, Vec<Vec2>)> {
+        let search = self.search_from([self.start], rules);
+        Some((search.distance(&self.end)?, search.path_to(&self.end)?))
     }
 
-    fn left(&self, current_point: &Point, ascend: bool) -> Option<Point> {
-        if current_point.x == 0 {
-            return None;
+    /// The heightmap as letters, with every step of `path` but the last replaced by an arrow
+    /// pointing at the next step
+    pub fn render_path(&self, path: &[Vec2]) -> String {
+        let mut cells = self.map.map(|height| char::from(b'a' + *height as u8));
+        cells[self.start] = 'S';
+        cells[self.end] = 'E';
+        for step in path.windows(2) {
+            cells[step[0]] = arrow(step[1] - step[0]);
         }
 
-        let to = Point {
-            x: current_point.x - 1,
-            y: current_point.y,
-        };
+        cells
+            .rows()
+            .map(|row| row.iter().collect::<String>())
+            .collect::<Vec<_>>()
+            .join("\n")
+    }
 
-        if ascend {
-            if self.can_ascend(current_point, &to) {
-                Some(to)
-            } else {
-                None
-            }
-        } else {
-            if self.can_descend(current_point, &to) {
-                Some(to)
-            } else {
-                None
-            }
+    /// The heightmap as an SVG image, one square per cell coloured by height, with `path` drawn
+    /// over the top as a line through the middle of each cell
+    pub fn to_svg(&self, path: &[Vec2]) -> String {
+        let width = self.map.width() as isize * SVG_CELL_SIZE;
+        let height = self.map.height() as isize * SVG_CELL_SIZE;
+        let mut svg = format!(
+            "<svg xmlns=\"http://www.w3.org/2000/svg\" width=\"{width}\" height=\"{height}\">\n"
+        );
+
+        for point in self.map.positions() {
+            svg += &format!(
+                concat!(
+                    "  <rect x=\"{}\" y=\"{}\" width=\"{size}\" height=\"{size}\" ",
+                    "fill=\"{}\"/>\n"
+                ),
+                point.x * SVG_CELL_SIZE,
+                point.y * SVG_CELL_SIZE,
+                height_colour(self.height_at(point)),
+                size = SVG_CELL_SIZE,
+            );
         }
-    }
 
-    fn right(&self, current_point: &Point, ascend: bool) -> Option<Point> {
-        if current_point.x >= self.width - 1 {
-            return None;
+        if !path.is_empty() {
+            let points: Vec<String> = path
+                .iter()
+                .map(|point| {
+                    let centre = *point * SVG_CELL_SIZE + Vec2::new(1, 1) * (SVG_CELL_SIZE / 2);
+                    format!("{},{}", centre.x, centre.y)
+                })
+                .collect();
+            svg += &format!(
+                "  <polyline points=\"{}\" fill=\"none\" stroke=\"red\" stroke-width=\"2\"/>\n",
+                points.join(" ")
+            );
         }
 
-        let to = Point {
-            x: current_point.x + 1,
-            y: current_point.y,
-        };
+        svg + "</svg>\n"
+    }
 
-        if ascend {
-            if self.can_ascend(current_point, &to) {
-                Some(to)
-            } else {
-                None
-            }
-        } else {
-            if self.can_descend(current_point, &to) {
-                Some(to)
-            } else {
-                None
-            }
-        }
+    pub fn height_at(&self, point: Vec2) -> usize {
+        self.map[point]
     }
 }
 
-fn parse_location(input: &str) -> IResult<&str, Location> {
-    let (input, result) = none_of("\n")(input)?;
-    let location = Location::new(result);
-
-    IResult::Ok((input, location))
+#[aoc_generator(day12)]
+fn input_generator(input: &str) -> Result<Map, ParseError> {
+    Map::parse(input).map_err(|e| e.to_parse_error(input))
 }
 
-fn parse_line(input: &str) -> IResult<&str, Vec<Location>> {
-    many1(parse_location)(input)
-}
+/// The fewest steps a route takes, or `None` if there's no route at all
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Steps(pub Option<usize>);
 
-#[aoc_generator(day12)]
-fn input_generator(input: &str) -> Vec<Vec<Location>> {
-    separated_list1(char('\n'), parse_line)(input)
-        .ok()
-        .unwrap()
-        .1
+impl Display for Steps {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self.0 {
+            Some(steps) => write!(f, "{steps}"),
+            None => write!(f, "no route"),
+        }
+    }
 }
 
 #[aoc(day12, part1)]
-fn part1(input: &Vec<Vec<Location>>) -> usize {
-    let ascend = true;
-    let mut map = Map::new(input, ascend);
-    map.traverse(&[map.start.clone()], ascend);
-
-    *map.distance_from_start
-        .get(&map.end)
-        .expect("No end distance")
+fn part1(map: &Map) -> Steps {
+    Steps(
+        map.shortest_path(&ClimbingRules::PUZZLE)
+            .map(|(steps, _)| steps),
+    )
 }
 
 #[aoc(day12, part2)]
-fn part2(input: &Vec<Vec<Location>>) -> usize {
-    let ascend = false;
-    let mut map = Map::new(input, ascend);
-    map.traverse(&[map.end.clone()], ascend);
+fn part2(map: &Map) -> Steps {
+    let lowest = map.select(&[Selector::Height('a')]);
 
-    let mut a_points: Vec<Point> = vec![];
+    Steps(map.shortest_distance(&lowest, &[map.end], &ClimbingRules::PUZZLE))
+}
 
-    for y in 0..map.height {
-        for x in 0..map.width {
-            if map.height_at(x, y) == 0 {
-                a_points.push(Point { x, y })
-            }
-        }
+pub struct Day12;
+
+impl Solution for Day12 {
+    type Input = Map;
+    type Answer1 = Steps;
+    type Answer2 = Steps;
+
+    fn parse(input: &str) -> Result<Self::Input, ParseError> {
+        input_generator(input)
     }
 
-    *a_points
-        .iter()
-        .filter_map(|p| map.distance_from_start.get(p))
-        .min()
-        .expect("No min")
+    fn part1(input: &Self::Input) -> Self::Answer1 {
+        part1(input)
+    }
+
+    fn part2(input: &Self::Input) -> Self::Answer2 {
+        part2(input)
+    }
 }
 
 #[cfg(test)]
@@ -329,13 +526,229 @@ abdefghi";
 
     #[test]
     fn part1_test() {
-        let input = input_generator(INPUT_STR);
-        assert_eq!(part1(&input), 31);
+        let input = input_generator(INPUT_STR).unwrap();
+        assert_eq!(part1(&input), Steps(Some(31)));
     }
 
     #[test]
     fn part2_test() {
-        let input = input_generator(INPUT_STR);
-        assert_eq!(part2(&input), 29);
+        let input = input_generator(INPUT_STR).unwrap();
+        assert_eq!(part2(&input), Steps(Some(29)));
+    }
+
+    #[test]
+    fn no_route_test() {
+        // The end is far too high to climb to
+        let input = input_generator("SbcE").unwrap();
+        assert_eq!(part1(&input), Steps(None));
+        assert_eq!(part2(&input), Steps(None));
+        assert_eq!(part1(&input).to_string(), "no route");
+    }
+
+    #[test]
+    fn render_path_test() {
+        let map = input_generator(INPUT_STR).unwrap();
+        let (_, path) = map.shortest_path(&ClimbingRules::PUZZLE).unwrap();
+        assert_eq!(path.first(), Some(&map.start));
+        assert_eq!(path.last(), Some(&map.end));
+
+        let rendered = map.render_path(&path);
+        assert_eq!(rendered.lines().count(), 5);
+        assert_eq!(rendered.matches(['^', 'v', '<', '>']).count(), 31);
+        assert!(rendered.contains('E'));
+    }
+
+    #[test]
+    fn to_svg_test() {
+        let map = input_generator(INPUT_STR).unwrap();
+        let (_, path) = map.shortest_path(&ClimbingRules::PUZZLE).unwrap();
+        let svg = map.to_svg(&path);
+        assert!(svg.starts_with("<svg "));
+        assert_eq!(svg.matches("<rect ").count(), 40);
+        assert_eq!(svg.matches("<polyline ").count(), 1);
+        assert!(svg.contains(&format!("fill=\"{}\"", height_colour(25))));
+    }
+
+    #[test]
+    fn climbing_rules_test() {
+        let map = input_generator(INPUT_STR).unwrap();
+        let distance = |rules: ClimbingRules| map.shortest_path(&rules).map(|(cost, _)| cost);
+
+        let anything_goes = ClimbingRules {
+            max_climb: 25,
+            ..ClimbingRules::PUZZLE
+        };
+        assert_eq!(distance(anything_goes), Some(7));
+
+        let diagonal = ClimbingRules {
+            diagonal: true,
+            ..ClimbingRules::PUZZLE
+        };
+        assert_eq!(distance(diagonal), Some(27));
+
+        let no_dropping = ClimbingRules {
+            max_drop: Some(0),
+            ..ClimbingRules::PUZZLE
+        };
+        assert_eq!(distance(no_dropping), Some(31));
+
+        let flat = ClimbingRules {
+            max_climb: 0,
+            ..ClimbingRules::PUZZLE
+        };
+        assert_eq!(distance(flat), None);
+
+        // Each level climbed costs 10 extra, so the cheapest route never climbs more than it must
+        let weighted = ClimbingRules {
+            max_climb: 25,
+            step_cost: |delta| 1 + delta.max(0) as usize * 10,
+            ..ClimbingRules::PUZZLE
+        };
+        assert_eq!(distance(weighted), Some(7 + 25 * 10));
+    }
+
+    #[test]
+    fn selector_test() {
+        assert_eq!("S".parse(), Ok(Selector::Start));
+        assert_eq!("q".parse(), Ok(Selector::Height('q')));
+        assert_eq!("3, 4".parse(), Ok(Selector::Point(Vec2::new(3, 4))));
+        assert!("A".parse::<Selector>().is_err());
+        assert!("3,x".parse::<Selector>().is_err());
+    }
+
+    #[test]
+    fn queries_test() {
+        let map = Map::parse(INPUT_STR).unwrap();
+        let rules = ClimbingRules::PUZZLE;
+
+        let lowest = map.select(&[Selector::Height('a')]);
+        assert_eq!(lowest.len(), 6);
+        assert!(map.select(&[Selector::Height('A')]).is_empty());
+        assert!(map.select(&[Selector::Height('{')]).is_empty());
+        let targets = map.select(&[Selector::End, Selector::Point(Vec2::new(7, 0))]);
+        assert_eq!(map.shortest_distance(&lowest, &targets, &rules), Some(13));
+
+        let field = map.distance_field(&[map.end], &rules);
+        assert_eq!(field[map.end], Some(0));
+        assert_eq!(field[Vec2::new(4, 2)], Some(1));
+
+        assert!(map.unreachable(&[map.start], &rules).is_empty());
+        // Without changing height, the end can only reach the other 'z' next to it
+        let flat = ClimbingRules {
+            max_climb: 0,
+            max_drop: Some(0),
+            ..rules
+        };
+        assert_eq!(map.unreachable(&[map.end], &flat).len(), 38);
+    }
+
+    /// A map whose only route is a corridor of `a`s snaking back and forth between walls of
+    /// `z`s, climbing a ramp to the end at the very last moment
+    fn snake(width: usize, corridors: usize) -> String {
+        let mut rows = vec![];
+        for corridor in 0..corridors {
+            let mut row = vec!['a'; width];
+            if corridor == corridors - 1 {
+                for (x, cell) in row.iter_mut().enumerate().take(25) {
+                    *cell = char::from(b'a' + 25 - x as u8);
+                }
+                row[0] = 'E';
+            }
+            // Even corridors run left to right, odd ones right to left
+            if corridor % 2 == 0 {
+                row.reverse();
+            }
+            rows.push(row);
+
+            if corridor < corridors - 1 {
+                let mut wall = vec!['z'; width];
+                let gap = if corridor % 2 == 0 { width - 1 } else { 0 };
+                wall[gap] = 'a';
+                rows.push(wall);
+            }
+        }
+        rows[0][0] = 'S';
+
+        rows.iter()
+            .map(|row| row.iter().collect::<String>())
+            .collect::<Vec<_>>()
+            .join("\n")
+    }
+
+    #[test]
+    fn snake_stress_test() {
+        let (width, corridors) = (2000, 1000);
+        let input = input_generator(&snake(width, corridors)).unwrap();
+        assert_eq!(input.map.height(), 1999);
+        assert_eq!(
+            part1(&input),
+            Steps(Some(corridors * (width - 1) + (corridors - 1) * 2))
+        );
+    }
+
+    #[test]
+    fn validation_test() {
+        assert_eq!(
+            Map::parse("Sab\nabc\nab\nabE").err(),
+            Some(MapError::RaggedRow {
+                y: 2,
+                expected: 3,
+                found: 2,
+            })
+        );
+        assert_eq!(
+            Map::parse("Sab\naAc\nabE").err(),
+            Some(MapError::InvalidHeight {
+                at: Vec2::new(1, 1),
+                found: 'A',
+            })
+        );
+        assert_eq!(Map::parse("aab\nabE").err(), Some(MapError::MissingStart));
+        assert_eq!(Map::parse("Sab\nabc").err(), Some(MapError::MissingEnd));
+        assert_eq!(
+            Map::parse("SaE\naSE").err(),
+            Some(MapError::DuplicateStart(Vec2::new(0, 0), Vec2::new(1, 1)))
+        );
+        assert_eq!(Map::parse("").err(), Some(MapError::Empty));
+    }
+
+    #[test]
+    fn validation_parse_error_test() {
+        // Messages count from 1 like the parse error does
+        let ragged = "Sab\nabc\nab\nabE";
+        let error = Map::parse(ragged).err().unwrap();
+        assert_eq!(error.to_string(), "line 3 has 2 cells, expected 3");
+        assert_eq!(error.to_parse_error(ragged).line, 3);
+
+        let invalid = "Sab\naAc\nabE";
+        let error = Map::parse(invalid).err().unwrap();
+        assert_eq!(
+            error.to_string(),
+            "invalid height 'A' at line 2, column 2, expected a to z, S or E"
+        );
+        assert_eq!(
+            error.to_parse_error(invalid),
+            ParseError::new(12, 2, 2, "aAc")
+        );
+
+        let duplicate = "SaE\naSE";
+        let error = Map::parse(duplicate).err().unwrap();
+        assert_eq!(
+            error.to_string(),
+            "there are two starts (S), at line 1, column 1 and line 2, column 2"
+        );
+        assert_eq!(
+            error.to_parse_error(duplicate),
+            ParseError::new(12, 2, 2, "aSE")
+        );
+
+        assert_eq!(
+            input_generator("Sab\naAc\nabE").err(),
+            Some(ParseError::new(12, 2, 2, "aAc"))
+        );
+        assert_eq!(
+            input_generator("SaE\nabE").err(),
+            Some(ParseError::new(12, 2, 3, "abE"))
+        );
     }
 }
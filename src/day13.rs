@@ -4,12 +4,15 @@ use nom::{
     branch::alt,
     bytes::complete::tag,
     character::complete::char,
-    multi::{separated_list0, separated_list1},
+    combinator::{cut, map},
+    multi::separated_list1,
     sequence::{delimited, terminated},
     IResult,
 };
 
-use crate::parsers::parse_usize;
+use crate::error::ParseError;
+use crate::parsers::{blocks_of, finish, parse_usize};
+use crate::solution::Solution;
 
 #[derive(Debug, Clone)]
 enum Value {
@@ -24,11 +27,7 @@ impl PartialEq for Value {
             (Value::Int(_), Value::List(_)) => false,
             (Value::List(_), Value::Int(_)) => false,
             (Value::List(a), Value::List(b)) => {
-                if a.len() != b.len() {
-                    return false;
-                } else {
-                    return a.iter().zip(b).all(|(first, second)| first == second);
-                }
+                a.len() == b.len() && a.iter().zip(b).all(|(first, second)| first == second)
             }
         }
     }
@@ -99,7 +98,7 @@ fn compare_packets(first: &Packet, second: &Packet) -> Option<bool> {
 type Packet = Vec<Value>;
 
 #[derive(Debug, Clone)]
-struct PacketPair {
+pub struct PacketPair {
     first: Packet,
     second: Packet,
 }
@@ -108,11 +107,7 @@ impl PacketPair {
     fn compare(&self) -> bool {
         let result = compare_packets(&self.first, &self.second);
 
-        if let Some(true) = result {
-            return true;
-        }
-
-        return false;
+        matches!(result, Some(true))
     }
 }
 
@@ -123,9 +118,10 @@ fn parse_item(input: &str) -> IResult<&str, Value> {
 }
 
 fn parse_value(input: &str) -> IResult<&str, Value> {
-    let item = alt((parse_item, parse_value));
-    let inner = separated_list0(char(','), item);
-    let (input, result) = delimited(char('['), inner, char(']'))(input)?;
+    // Once a list has started every item is required, so a bad one is reported where it is
+    let items = separated_list1(char(','), cut(alt((parse_item, parse_value))));
+    let empty = map(tag("[]"), |_| vec![]);
+    let (input, result) = alt((empty, delimited(char('['), items, char(']'))))(input)?;
 
     IResult::Ok((input, Value::List(result)))
 }
@@ -149,25 +145,22 @@ fn parse_packet_pair(input: &str) -> IResult<&str, PacketPair> {
 }
 
 #[aoc_generator(day13)]
-fn input_generator(input: &str) -> Vec<PacketPair> {
-    separated_list1(tag("\n\n"), parse_packet_pair)(input)
-        .ok()
-        .unwrap()
-        .1
+fn input_generator(input: &str) -> Result<Vec<PacketPair>, ParseError> {
+    finish(13, input, blocks_of(parse_packet_pair))
 }
 
 #[aoc(day13, part1)]
-fn part1(input: &Vec<PacketPair>) -> usize {
+fn part1(input: &[PacketPair]) -> usize {
     input
         .iter()
         .enumerate()
         .map(|(index, packet_pair)| (index + 1, packet_pair.compare()))
-        .filter_map(|(index, result)| result.then(|| index))
+        .filter_map(|(index, result)| result.then_some(index))
         .sum()
 }
 
 #[aoc(day13, part2)]
-fn part2(input: &Vec<PacketPair>) -> usize {
+fn part2(input: &[PacketPair]) -> usize {
     let mut flat_input: Vec<Packet> = vec![];
     for pair in input {
         flat_input.push(pair.first.clone());
@@ -205,6 +198,26 @@ fn part2(input: &Vec<PacketPair>) -> usize {
     (index_1 + 1) * (index_2 + 1)
 }
 
+pub struct Day13;
+
+impl Solution for Day13 {
+    type Input = Vec<PacketPair>;
+    type Answer1 = usize;
+    type Answer2 = usize;
+
+    fn parse(input: &str) -> Result<Self::Input, ParseError> {
+        input_generator(input)
+    }
+
+    fn part1(input: &Self::Input) -> Self::Answer1 {
+        part1(input)
+    }
+
+    fn part2(input: &Self::Input) -> Self::Answer2 {
+        part2(input)
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -235,13 +248,20 @@ mod tests {
 
     #[test]
     fn part1_test() {
-        let input = input_generator(INPUT_STR);
+        let input = input_generator(INPUT_STR).unwrap();
         assert_eq!(part1(&input), 13);
     }
 
     #[test]
     fn part2_test() {
-        let input = input_generator(INPUT_STR);
+        let input = input_generator(INPUT_STR).unwrap();
         assert_eq!(part2(&input), 140);
     }
+
+    #[test]
+    fn input_generator_error_test() {
+        let input = INPUT_STR.replace("[[1],[2,3,4]]", "[1,x]");
+        let error = input_generator(&input).err().unwrap();
+        assert_eq!(error, ParseError::new(13, 4, 4, "[1,x]"));
+    }
 }
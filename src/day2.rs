@@ -1,4 +1,7 @@
-use std::convert::From;
+use std::convert::{From, TryFrom};
+
+use crate::error::ParseError;
+use crate::solution::Solution;
 
 #[derive(Clone, Copy)]
 enum Left {
@@ -7,13 +10,15 @@ enum Left {
     C,
 }
 
-impl From<char> for Left {
-    fn from(c: char) -> Self {
+impl TryFrom<char> for Left {
+    type Error = char;
+
+    fn try_from(c: char) -> Result<Self, Self::Error> {
         match c {
-            'A' => Self::A,
-            'B' => Self::B,
-            'C' => Self::C,
-            _ => panic!("Could not convert char '{}'", c),
+            'A' => Ok(Self::A),
+            'B' => Ok(Self::B),
+            'C' => Ok(Self::C),
+            _ => Err(c),
         }
     }
 }
@@ -67,13 +72,15 @@ impl From<Right> for Move {
     }
 }
 
-impl From<char> for Right {
-    fn from(c: char) -> Self {
+impl TryFrom<char> for Right {
+    type Error = char;
+
+    fn try_from(c: char) -> Result<Self, Self::Error> {
         match c {
-            'X' => Self::X,
-            'Y' => Self::Y,
-            'Z' => Self::Z,
-            _ => panic!("Could not convert char '{}'", c),
+            'X' => Ok(Self::X),
+            'Y' => Ok(Self::Y),
+            'Z' => Ok(Self::Z),
+            _ => Err(c),
         }
     }
 }
@@ -110,7 +117,7 @@ impl From<Right> for RoundResult {
     }
 }
 
-struct StrategyGuide {
+pub struct StrategyGuide {
     rounds: Vec<(Left, Right)>,
 }
 
@@ -130,19 +137,23 @@ fn score2(round: &(Left, Right)) -> usize {
 }
 
 #[aoc_generator(day2)]
-fn input_generator(input: &str) -> StrategyGuide {
+fn input_generator(input: &str) -> Result<StrategyGuide, ParseError> {
     let rounds = input
-        .split("\n")
-        .map(|line| {
+        .split('\n')
+        .enumerate()
+        .map(|(index, line)| {
+            let error = |column| ParseError::new(2, index + 1, column, line);
             let chars: Vec<char> = line.chars().collect();
-            println!("line: {}, chars: {:#?}", &line, &chars);
-            let left = chars[0];
-            let right = chars[2];
-            (Left::from(left), Right::from(right))
+            if chars.len() != 3 || chars[1] != ' ' {
+                return Err(error(1));
+            }
+            let left = Left::try_from(chars[0]).map_err(|_| error(1))?;
+            let right = Right::try_from(chars[2]).map_err(|_| error(3))?;
+            Ok((left, right))
         })
-        .collect();
+        .collect::<Result<_, _>>()?;
 
-    StrategyGuide { rounds }
+    Ok(StrategyGuide { rounds })
 }
 
 #[aoc(day2, part1)]
@@ -155,6 +166,26 @@ fn part2(input: &StrategyGuide) -> usize {
     input.rounds.iter().map(score2).sum()
 }
 
+pub struct Day2;
+
+impl Solution for Day2 {
+    type Input = StrategyGuide;
+    type Answer1 = usize;
+    type Answer2 = usize;
+
+    fn parse(input: &str) -> Result<Self::Input, ParseError> {
+        input_generator(input)
+    }
+
+    fn part1(input: &Self::Input) -> Self::Answer1 {
+        part1(input)
+    }
+
+    fn part2(input: &Self::Input) -> Self::Answer2 {
+        part2(input)
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -165,13 +196,19 @@ C Z";
 
     #[test]
     fn part1_test() {
-        let input = input_generator(INPUT_STR);
+        let input = input_generator(INPUT_STR).unwrap();
         assert_eq!(part1(&input), 15);
     }
 
     #[test]
     fn part2_test() {
-        let input = input_generator(INPUT_STR);
+        let input = input_generator(INPUT_STR).unwrap();
         assert_eq!(part2(&input), 12);
     }
+
+    #[test]
+    fn input_generator_error_test() {
+        let error = input_generator("A Y\nB Q\nC Z").err().unwrap();
+        assert_eq!(error, ParseError::new(2, 2, 3, "B Q"));
+    }
 }
@@ -1,12 +1,21 @@
 use std::collections::HashSet;
 
+use crate::error::ParseError;
+use crate::solution::Solution;
+
 type Rucksack = Vec<char>;
 
 #[aoc_generator(day3)]
-fn input_generator(input: &str) -> Vec<Rucksack> {
+fn input_generator(input: &str) -> Result<Vec<Rucksack>, ParseError> {
     input
-        .split("\n")
-        .map(|line| line.chars().collect())
+        .split('\n')
+        .enumerate()
+        .map(
+            |(index, line)| match line.chars().position(|c| !c.is_ascii_alphabetic()) {
+                Some(position) => Err(ParseError::new(3, index + 1, position + 1, line)),
+                None => Ok(line.chars().collect()),
+            },
+        )
         .collect()
 }
 
@@ -58,22 +67,41 @@ fn score(c: &char) -> usize {
         65..=90 => char_u8 - 64 + 26,
         _ => panic!("bad character"),
     };
-    println!("char '{}' = {}", c, score);
     score as usize
 }
 
 #[aoc(day3, part1)]
-fn part1(input: &Vec<Rucksack>) -> usize {
+fn part1(input: &[Rucksack]) -> usize {
     let duplicates: Vec<_> = input.iter().map(find_duplicate).collect();
     duplicates.iter().map(score).sum()
 }
 
 #[aoc(day3, part2)]
-fn part2(input: &Vec<Rucksack>) -> usize {
+fn part2(input: &[Rucksack]) -> usize {
     let badges: Vec<_> = input.chunks(3).map(find_badge).collect();
     badges.iter().map(score).sum()
 }
 
+pub struct Day3;
+
+impl Solution for Day3 {
+    type Input = Vec<Rucksack>;
+    type Answer1 = usize;
+    type Answer2 = usize;
+
+    fn parse(input: &str) -> Result<Self::Input, ParseError> {
+        input_generator(input)
+    }
+
+    fn part1(input: &Self::Input) -> Self::Answer1 {
+        part1(input)
+    }
+
+    fn part2(input: &Self::Input) -> Self::Answer2 {
+        part2(input)
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -87,13 +115,13 @@ CrZsJsPPZsGzwwsLwLmpwMDw";
 
     #[test]
     fn part1_test() {
-        let input = input_generator(INPUT_STRING);
+        let input = input_generator(INPUT_STRING).unwrap();
         assert_eq!(part1(&input), 157);
     }
 
     #[test]
     fn part2_test() {
-        let input = input_generator(INPUT_STRING);
+        let input = input_generator(INPUT_STRING).unwrap();
         assert_eq!(part2(&input), 70);
     }
 }
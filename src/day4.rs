@@ -1,12 +1,13 @@
 use nom::character::complete::char;
-use nom::multi::separated_list1;
-use nom::{character::complete::digit1, sequence::separated_pair, IResult};
+use nom::{sequence::separated_pair, IResult};
 
-use crate::parsers::parse_usize;
+use crate::error::ParseError;
+use crate::parsers::{finish, lines_of, parse_usize};
+use crate::solution::Solution;
 
 type Pair = (usize, usize);
 
-struct ElfPair {
+pub struct ElfPair {
     p1: Pair,
     p2: Pair,
 }
@@ -49,11 +50,7 @@ impl ElfPair {
 }
 
 fn parse_pair(input: &str) -> IResult<&str, (usize, usize)> {
-    let (input, result) = separated_pair(digit1, char('-'), digit1)(input)?;
-    let (_, val1) = parse_usize(result.0)?;
-    let (_, val2) = parse_usize(result.1)?;
-
-    IResult::Ok((input, (val1, val2)))
+    separated_pair(parse_usize, char('-'), parse_usize)(input)
 }
 
 fn parse_elf_pair(input: &str) -> IResult<&str, ElfPair> {
@@ -65,14 +62,12 @@ fn parse_elf_pair(input: &str) -> IResult<&str, ElfPair> {
 }
 
 #[aoc_generator(day4)]
-fn input_generator(input: &str) -> Vec<ElfPair> {
-    let result = separated_list1(char('\n'), parse_elf_pair)(input);
-
-    result.ok().expect("Bad parse").1
+fn input_generator(input: &str) -> Result<Vec<ElfPair>, ParseError> {
+    finish(4, input, lines_of(parse_elf_pair))
 }
 
 #[aoc(day4, part1)]
-fn part1(input: &Vec<ElfPair>) -> usize {
+fn part1(input: &[ElfPair]) -> usize {
     input
         .iter()
         .filter(|elf_pair| elf_pair.full_overlap())
@@ -80,13 +75,33 @@ fn part1(input: &Vec<ElfPair>) -> usize {
 }
 
 #[aoc(day4, part2)]
-fn part2(input: &Vec<ElfPair>) -> usize {
+fn part2(input: &[ElfPair]) -> usize {
     input
         .iter()
         .filter(|elf_pair| elf_pair.partial_overlap())
         .count()
 }
 
+pub struct Day4;
+
+impl Solution for Day4 {
+    type Input = Vec<ElfPair>;
+    type Answer1 = usize;
+    type Answer2 = usize;
+
+    fn parse(input: &str) -> Result<Self::Input, ParseError> {
+        input_generator(input)
+    }
+
+    fn part1(input: &Self::Input) -> Self::Answer1 {
+        part1(input)
+    }
+
+    fn part2(input: &Self::Input) -> Self::Answer2 {
+        part2(input)
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -100,13 +115,19 @@ mod tests {
 
     #[test]
     fn part1_test() {
-        let input = input_generator(INPUT_STR);
+        let input = input_generator(INPUT_STR).unwrap();
         assert_eq!(part1(&input), 2);
     }
 
     #[test]
     fn part2_test() {
-        let input = input_generator(INPUT_STR);
+        let input = input_generator(INPUT_STR).unwrap();
         assert_eq!(part2(&input), 4);
     }
+
+    #[test]
+    fn input_generator_error_test() {
+        let error = input_generator("2-4,6-8\n2-x,4-5").err().unwrap();
+        assert_eq!(error, ParseError::new(4, 2, 3, "2-x,4-5"));
+    }
 }
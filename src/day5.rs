@@ -6,10 +6,12 @@ use nom::multi::separated_list1;
 use nom::sequence::{delimited, separated_pair};
 use nom::IResult;
 
-use crate::parsers::parse_usize;
+use crate::error::ParseError;
+use crate::parsers::{finish, lines_of, parse_usize};
+use crate::solution::Solution;
 
 #[derive(Debug)]
-struct Move {
+pub struct Move {
     num_crates: usize,
     from_stack: usize,
     to_stack: usize,
@@ -31,8 +33,10 @@ fn parse_crate_row(input: &str) -> IResult<&str, Vec<Option<char>>> {
     separated_list1(char(' '), alt((parse_crate, parse_crate_space)))(input)
 }
 
-fn parse_crates(input: &str) -> IResult<&str, Vec<Vec<Option<char>>>> {
-    separated_list1(char('\n'), parse_crate_row)(input)
+type CrateRows = Vec<Vec<Option<char>>>;
+
+fn parse_crates(input: &str) -> IResult<&str, CrateRows> {
+    lines_of(parse_crate_row)(input)
 }
 
 fn parse_move_n(input: &str) -> IResult<&str, usize> {
@@ -57,7 +61,7 @@ fn parse_move(input: &str) -> IResult<&str, Move> {
 }
 
 #[derive(Clone)]
-struct Stacks {
+pub struct Stacks {
     stacks: Vec<Vec<char>>,
 }
 
@@ -98,9 +102,24 @@ fn take_newline(input: &str) -> IResult<&str, char> {
     char('\n')(input)
 }
 
+fn parse_drawing_and_moves(input: &str) -> IResult<&str, (CrateRows, Vec<Move>)> {
+    let (input, crates) = parse_crates(input)?;
+    // the newline at the end of the last crate row
+    let (input, _) = take_newline(input)?;
+    // the number line
+    let (input, _) = take_until_double_newline(input)?;
+    // the empty line
+    let (input, _) = take_newline(input)?;
+    let (input, _) = take_newline(input)?;
+    let (input, moves) = lines_of(parse_move)(input)?;
+
+    IResult::Ok((input, (crates, moves)))
+}
+
 #[aoc_generator(day5)]
-fn input_generator(input: &str) -> (Stacks, Vec<Move>) {
-    let (input, mut crates) = parse_crates(input).ok().unwrap();
+fn input_generator(input: &str) -> Result<(Stacks, Vec<Move>), ParseError> {
+    let (mut crates, moves) = finish(5, input, parse_drawing_and_moves)?;
+
     // all crate vectors should be the same length
     let num_stacks = crates[0].len();
     crates.reverse();
@@ -110,25 +129,12 @@ fn input_generator(input: &str) -> (Stacks, Vec<Move>) {
             .iter()
             .enumerate()
             .for_each(|(index, ch)| match ch {
-                Some(c) => {
-                    if c != &' ' {
-                        stacks[index].push(*c)
-                    }
-                }
-                None => {}
+                Some(' ') | None => {}
+                Some(c) => stacks[index].push(*c),
             })
     });
 
-    // the newline at the end of the last crate row
-    let (input, _) = take_newline(input).ok().unwrap();
-    // the number line
-    let (input, _) = take_until_double_newline(input).ok().unwrap();
-    // the empty line
-    let (input, _) = take_newline(input).ok().unwrap();
-    let (input, _) = take_newline(input).ok().unwrap();
-
-    let (_, moves) = separated_list1(char('\n'), parse_move)(input).ok().unwrap();
-    (Stacks { stacks }, moves)
+    Ok((Stacks { stacks }, moves))
 }
 
 #[aoc(day5, part1)]
@@ -151,6 +157,26 @@ fn part2(input: &(Stacks, Vec<Move>)) -> String {
     stacks.stacks.iter().map(|s| s.last().unwrap()).collect()
 }
 
+pub struct Day5;
+
+impl Solution for Day5 {
+    type Input = (Stacks, Vec<Move>);
+    type Answer1 = String;
+    type Answer2 = String;
+
+    fn parse(input: &str) -> Result<Self::Input, ParseError> {
+        input_generator(input)
+    }
+
+    fn part1(input: &Self::Input) -> Self::Answer1 {
+        part1(input)
+    }
+
+    fn part2(input: &Self::Input) -> Self::Answer2 {
+        part2(input)
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -159,11 +185,10 @@ mod tests {
     fn parser_test1() {
         let input = "[A]     [B]     [C]";
         let result: Vec<char> = parse_crate_row(input)
-            .ok()
             .expect("bad parse")
             .1
             .into_iter()
-            .filter_map(|x| x)
+            .flatten()
             .collect();
         assert_eq!(result, vec!['A', ' ', 'B', ' ', 'C']);
     }
@@ -172,11 +197,10 @@ mod tests {
     fn parser_test2() {
         let input = "    [A]     [B]    ";
         let result: Vec<char> = parse_crate_row(input)
-            .ok()
             .expect("bad parse")
             .1
             .into_iter()
-            .filter_map(|x| x)
+            .flatten()
             .collect();
         assert_eq!(result, vec![' ', 'A', ' ', 'B', ' ']);
     }
@@ -193,13 +217,13 @@ move 1 from 1 to 2";
 
     #[test]
     fn part1_test() {
-        let input = input_generator(INPUT_STR);
+        let input = input_generator(INPUT_STR).unwrap();
         assert_eq!(part1(&input), "CMZ");
     }
 
     #[test]
     fn part2_test() {
-        let input = input_generator(INPUT_STR);
+        let input = input_generator(INPUT_STR).unwrap();
         assert_eq!(part2(&input), "MCD");
     }
 }
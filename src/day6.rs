@@ -2,17 +2,21 @@ use std::collections::HashSet;
 
 use nom::{character::complete::anychar, multi::many1, IResult};
 
+use crate::error::ParseError;
+use crate::parsers::finish;
+use crate::solution::Solution;
+
 fn parse_chars(input: &str) -> IResult<&str, Vec<char>> {
     many1(anychar)(input)
 }
 
 #[aoc_generator(day6)]
-fn input_generator(input: &str) -> Vec<char> {
-    parse_chars(input).ok().unwrap().1
+fn input_generator(input: &str) -> Result<Vec<char>, ParseError> {
+    finish(6, input, parse_chars)
 }
 
 #[aoc(day6, part1)]
-fn part1(input: &Vec<char>) -> usize {
+fn part1(input: &[char]) -> usize {
     let marker_length = 4;
     input
         .windows(marker_length)
@@ -28,7 +32,7 @@ fn part1(input: &Vec<char>) -> usize {
 }
 
 #[aoc(day6, part2)]
-fn part2(input: &Vec<char>) -> usize {
+fn part2(input: &[char]) -> usize {
     let marker_length = 14;
     input
         .windows(marker_length)
@@ -43,6 +47,26 @@ fn part2(input: &Vec<char>) -> usize {
         + marker_length
 }
 
+pub struct Day6;
+
+impl Solution for Day6 {
+    type Input = Vec<char>;
+    type Answer1 = usize;
+    type Answer2 = usize;
+
+    fn parse(input: &str) -> Result<Self::Input, ParseError> {
+        input_generator(input)
+    }
+
+    fn part1(input: &Self::Input) -> Self::Answer1 {
+        part1(input)
+    }
+
+    fn part2(input: &Self::Input) -> Self::Answer2 {
+        part2(input)
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -51,13 +75,13 @@ mod tests {
 
     #[test]
     fn part1_test() {
-        let input = input_generator(INPUT_STR);
+        let input = input_generator(INPUT_STR).unwrap();
         assert_eq!(part1(&input), 7);
     }
 
     #[test]
     fn part2_test() {
-        let input = input_generator(INPUT_STR);
+        let input = input_generator(INPUT_STR).unwrap();
         assert_eq!(part2(&input), 19);
     }
 }
@@ -1,10 +1,13 @@
 use std::collections::{HashMap, HashSet};
 
+use crate::error::ParseError;
+use crate::solution::Solution;
+
 #[derive(Debug)]
-enum Line {
+pub enum Line {
     Ls,
     Cd(String),
-    Dir(String),
+    Dir,
     File(usize, String),
 }
 
@@ -165,53 +168,47 @@ impl FileSystem {
                         Item::Directory(dir) => self.current_directory = dir.parent,
                     }
                 } else {
-                    let dir = Item::new_directory(&dir_name, self.current_directory);
+                    let dir = Item::new_directory(dir_name, self.current_directory);
                     self.current_directory = self.add_item(dir);
                 }
             }
-            Line::Dir(_) => {}
+            Line::Dir => {}
             Line::File(size, file_name) => {
-                let file = Item::new_file(&file_name, *size);
+                let file = Item::new_file(file_name, *size);
                 self.add_item(file);
             }
         }
     }
 }
 
+fn parse_line(line: &str) -> Option<Line> {
+    let parts: Vec<_> = line.split(' ').collect();
+
+    match parts[..] {
+        ["$", "ls"] => Some(Line::Ls),
+        ["$", "cd", dir_name] => Some(Line::Cd(String::from(dir_name))),
+        ["dir", _] => Some(Line::Dir),
+        [size, file_name] => size
+            .parse()
+            .ok()
+            .map(|size| Line::File(size, String::from(file_name))),
+        _ => None,
+    }
+}
+
 #[aoc_generator(day7)]
-fn input_generator(input: &str) -> Vec<Line> {
+fn input_generator(input: &str) -> Result<Vec<Line>, ParseError> {
     input
         .lines()
-        .map(|l| {
-            let s = l.split(' ').into_iter();
-            match s.clone().take(1).next() {
-                Some(first) => {
-                    if first == "$" {
-                        let v: Vec<_> = s.skip(1).collect();
-
-                        match v[0] {
-                            "ls" => Line::Ls,
-                            "cd" => Line::Cd(String::from(v[1])),
-                            _ => panic!("Bad parse"),
-                        }
-                    } else {
-                        let v: Vec<_> = s.collect();
-                        match v[0] {
-                            "dir" => Line::Dir(String::from(v[1])),
-                            size => Line::File(size.parse().unwrap(), String::from(v[1])),
-                        }
-                    }
-                }
-                None => panic!("Bad parse"),
-            }
-        })
+        .enumerate()
+        .map(|(index, l)| parse_line(l).ok_or_else(|| ParseError::new(7, index + 1, 1, l)))
         // Skip first line as it's always "$ cd /", and we assume that we start in a root dir
         .skip(1)
         .collect()
 }
 
 #[aoc(day7, part1)]
-fn part1(input: &Vec<Line>) -> usize {
+fn part1(input: &[Line]) -> usize {
     let mut fs = FileSystem::new();
 
     input.iter().for_each(|line| fs.execute(line));
@@ -234,7 +231,7 @@ fn part1(input: &Vec<Line>) -> usize {
 }
 
 #[aoc(day7, part2)]
-fn part2(input: &Vec<Line>) -> usize {
+fn part2(input: &[Line]) -> usize {
     let mut fs = FileSystem::new();
 
     input.iter().for_each(|line| fs.execute(line));
@@ -246,8 +243,8 @@ fn part2(input: &Vec<Line>) -> usize {
 
     let dirs: Vec<Directory> = fs
         .nodes
-        .iter()
-        .filter_map(|(_, item)| match item {
+        .values()
+        .filter_map(|item| match item {
             Item::File(_) => None,
             Item::Directory(dir) => Some(dir.clone()),
         })
@@ -268,6 +265,26 @@ fn part2(input: &Vec<Line>) -> usize {
 const TOTAL_DISK_SPACE: usize = 70000000;
 const GOAL_FREE_SPACE: usize = 30000000;
 
+pub struct Day7;
+
+impl Solution for Day7 {
+    type Input = Vec<Line>;
+    type Answer1 = usize;
+    type Answer2 = usize;
+
+    fn parse(input: &str) -> Result<Self::Input, ParseError> {
+        input_generator(input)
+    }
+
+    fn part1(input: &Self::Input) -> Self::Answer1 {
+        part1(input)
+    }
+
+    fn part2(input: &Self::Input) -> Self::Answer2 {
+        part2(input)
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -299,7 +316,8 @@ $ ls
 5626152 d.ext
 7214296 k
 ",
-        );
+        )
+        .unwrap();
         assert_eq!(part1(&input), 95437);
     }
 }
@@ -1,208 +1,93 @@
-type Trees = Vec<Vec<usize>>;
+use crate::error::ParseError;
+use crate::grid::Grid;
+use crate::solution::Solution;
+use crate::vec2::Vec2;
+
+type Trees = Grid<usize>;
 
 #[aoc_generator(day8)]
-fn input_generator(input: &str) -> Trees {
-    input
-        .split("\n")
-        .map(|row| {
-            row.chars()
-                .map(|char| (char.to_digit(10).expect("Char was not a digit") as usize))
-                .collect()
-        })
-        .collect()
+fn input_generator(input: &str) -> Result<Trees, ParseError> {
+    Grid::parse(8, input, |c| c.to_digit(10).map(|d| d as usize))
 }
 
-struct TreeGrid {
-    trees: Trees,
-    grid_size: usize,
-    max_grid_index: usize,
+struct TreeGrid<'a> {
+    trees: &'a Trees,
 }
 
-impl TreeGrid {
-    fn new(trees: Trees) -> Self {
-        let grid_size = trees[0].len();
-        let max_grid_index = grid_size - 1;
-        Self {
-            trees,
-            grid_size,
-            max_grid_index,
-        }
-    }
-
-    fn height_at(&self, x: usize, y: usize) -> usize {
-        if x >= self.grid_size || y >= self.grid_size {
-            panic!(
-                "x or y is greater than grid size {}\nx: {} y: {}",
-                self.grid_size, x, y
-            )
-        }
-
-        self.trees[y][x]
-    }
-
-    fn is_edge_tree(&self, x: usize, y: usize) -> bool {
-        if x == 0 || x == self.max_grid_index {
-            return true;
-        }
-
-        if y == 0 || y == self.max_grid_index {
-            return true;
-        }
-
-        false
-    }
-
-    fn visible_from_left(&self, initial_x: usize, initial_y: usize) -> (bool, usize) {
-        let mut visibility = 0;
-        let mut visible = true;
-
-        let is_edge_tree = self.is_edge_tree(initial_x, initial_y);
-        if !is_edge_tree {
-            let height = self.height_at(initial_x, initial_y);
-
-            let mut x = initial_x - 1;
-
-            loop {
-                visibility += 1;
-                let left_height = self.height_at(x, initial_y);
-                if left_height >= height {
-                    visible = false;
-                    break;
-                }
-                if x == 0 {
-                    break;
-                }
-                x -= 1;
-            }
-        }
-        (visible, visibility)
+impl<'a> TreeGrid<'a> {
+    fn new(trees: &'a Trees) -> Self {
+        Self { trees }
     }
 
-    fn visible_from_right(&self, initial_x: usize, initial_y: usize) -> (bool, usize) {
-        let mut visible = true;
+    /// Looks from the tree at `position` towards the edge in `direction`. Returns whether the
+    /// tree can be seen from that edge, and how many trees it can see before its view is blocked.
+    fn view(&self, position: Vec2, direction: Vec2) -> (bool, usize) {
+        let height = self.trees[position];
         let mut visibility = 0;
 
-        let is_edge_tree = self.is_edge_tree(initial_x, initial_y);
-        if !is_edge_tree {
-            let height = self.height_at(initial_x, initial_y);
-
-            let mut x = initial_x + 1;
-
-            while x < self.grid_size {
-                visibility += 1;
-                let right_height = self.height_at(x, initial_y);
-                if right_height >= height {
-                    visible = false;
-                    break;
-                }
-                x += 1;
+        for (_, other) in self.trees.ray(position, direction) {
+            visibility += 1;
+            if *other >= height {
+                return (false, visibility);
             }
         }
-        (visible, visibility)
-    }
 
-    fn visible_from_top(&self, initial_x: usize, initial_y: usize) -> (bool, usize) {
-        let mut visibility = 0;
-        let mut visible = true;
-        let is_edge_tree = self.is_edge_tree(initial_x, initial_y);
-        if !is_edge_tree {
-            let height = self.height_at(initial_x, initial_y);
-
-            let mut y = initial_y - 1;
-
-            loop {
-                visibility += 1;
-                let top_height = self.height_at(initial_x, y);
-                if top_height >= height {
-                    visible = false;
-                    break;
-                }
-                if y == 0 {
-                    break;
-                }
-                y -= 1;
-            }
-        }
-        (visible, visibility)
+        (true, visibility)
     }
 
-    fn visible_from_bottom(&self, initial_x: usize, initial_y: usize) -> (bool, usize) {
-        let mut visibility = 0;
-        let mut visible = true;
-        let is_edge_tree = self.is_edge_tree(initial_x, initial_y);
-        if !is_edge_tree {
-            let height = self.height_at(initial_x, initial_y);
-
-            let mut y = initial_y + 1;
-
-            while y < self.grid_size {
-                visibility += 1;
-                let top_height = self.height_at(initial_x, y);
-                if top_height >= height {
-                    visible = false;
-                    break;
-                }
-                y += 1;
-            }
-        }
-        (visible, visibility)
+    fn tree_is_visible(&self, position: Vec2) -> bool {
+        Vec2::DIRECTIONS_4
+            .iter()
+            .any(|direction| self.view(position, *direction).0)
     }
 
-    fn tree_is_visible(&self, x: usize, y: usize) -> bool {
-        let (left, _) = self.visible_from_left(x, y);
-        let (right, _) = self.visible_from_right(x, y);
-        let (top, _) = self.visible_from_top(x, y);
-        let (bottom, _) = self.visible_from_bottom(x, y);
-        let visible = left || right || top || bottom;
-        visible
-    }
-
-    fn visibility_score(&self, x: usize, y: usize) -> usize {
-        let height = self.height_at(x, y);
-        let (_, left) = self.visible_from_left(x, y);
-        let (_, right) = self.visible_from_right(x, y);
-        let (_, top) = self.visible_from_top(x, y);
-        let (_, bottom) = self.visible_from_bottom(x, y);
-        println!(
-            "x: {}, y: {}, height: {}\nleft: {}, right: {}, top: {}, bottom: {}",
-            x, y, height, left, right, top, bottom
-        );
-
-        left * right * top * bottom
+    fn visibility_score(&self, position: Vec2) -> usize {
+        Vec2::DIRECTIONS_4
+            .iter()
+            .map(|direction| self.view(position, *direction).1)
+            .product()
     }
 }
 
 #[aoc(day8, part1)]
 fn part1(input: &Trees) -> usize {
-    let tree_grid = TreeGrid::new(input.clone());
+    let tree_grid = TreeGrid::new(input);
 
-    let mut visible_count = 0;
-    for x in 0..tree_grid.grid_size {
-        for y in 0..tree_grid.grid_size {
-            if tree_grid.tree_is_visible(x, y) {
-                visible_count += 1;
-            }
-        }
-    }
-    visible_count
+    input
+        .positions()
+        .filter(|position| tree_grid.tree_is_visible(*position))
+        .count()
 }
 
 #[aoc(day8, part2)]
 fn part2(input: &Trees) -> usize {
-    let tree_grid = TreeGrid::new(input.clone());
-    let mut max = 0;
-
-    for x in 0..tree_grid.grid_size {
-        for y in 0..tree_grid.grid_size {
-            let score = tree_grid.visibility_score(x, y);
-            println!("score: {}", score);
-            if score > max {
-                max = score;
-            }
-        }
+    let tree_grid = TreeGrid::new(input);
+
+    input
+        .positions()
+        .map(|position| tree_grid.visibility_score(position))
+        .max()
+        .unwrap_or(0)
+}
+
+pub struct Day8;
+
+impl Solution for Day8 {
+    type Input = Trees;
+    type Answer1 = usize;
+    type Answer2 = usize;
+
+    fn parse(input: &str) -> Result<Self::Input, ParseError> {
+        input_generator(input)
     }
 
-    max
+    fn part1(input: &Self::Input) -> Self::Answer1 {
+        part1(input)
+    }
+
+    fn part2(input: &Self::Input) -> Self::Answer2 {
+        part2(input)
+    }
 }
 
 #[cfg(test)]
@@ -218,7 +103,8 @@ mod tests {
 33549
 35390
 ",
-        );
+        )
+        .unwrap();
         assert_eq!(part1(&input), 21);
     }
 
@@ -231,7 +117,15 @@ mod tests {
 33549
 35390
 ",
-        );
+        )
+        .unwrap();
         assert_eq!(part2(&input), 8);
     }
+
+    #[test]
+    fn non_square_test() {
+        let input = input_generator("3037\n2551\n6533").unwrap();
+        assert_eq!(part1(&input), 12);
+        assert_eq!(part2(&input), 1);
+    }
 }
@@ -1,7 +1,11 @@
-use std::{collections::HashSet, convert::From};
+use std::{collections::HashSet, convert::TryFrom};
+
+use crate::error::ParseError;
+use crate::solution::Solution;
+use crate::vec2::Vec2;
 
 #[derive(Debug)]
-enum Direction {
+pub enum Direction {
     Up(usize),
     Down(usize),
     Left(usize),
@@ -17,200 +21,123 @@ impl Direction {
             Direction::Right(distance) => *distance,
         }
     }
+
+    fn unit(&self) -> Vec2 {
+        match self {
+            Direction::Up(_) => Vec2::UP,
+            Direction::Down(_) => Vec2::DOWN,
+            Direction::Left(_) => Vec2::LEFT,
+            Direction::Right(_) => Vec2::RIGHT,
+        }
+    }
 }
 
-impl From<(&str, usize)> for Direction {
-    fn from((direction, distance): (&str, usize)) -> Self {
+impl TryFrom<(&str, usize)> for Direction {
+    type Error = ();
+
+    fn try_from((direction, distance): (&str, usize)) -> Result<Self, Self::Error> {
         match direction {
-            "U" => Self::Up(distance),
-            "D" => Self::Down(distance),
-            "L" => Self::Left(distance),
-            "R" => Self::Right(distance),
-            _ => panic!("Bad direction"),
+            "U" => Ok(Self::Up(distance)),
+            "D" => Ok(Self::Down(distance)),
+            "L" => Ok(Self::Left(distance)),
+            "R" => Ok(Self::Right(distance)),
+            _ => Err(()),
         }
     }
 }
 
 #[aoc_generator(day9)]
-fn input_generator(input: &str) -> Vec<Direction> {
+fn input_generator(input: &str) -> Result<Vec<Direction>, ParseError> {
     input
-        .split("\n")
-        .map(|line| {
-            let line_split: Vec<_> = line.split(" ").collect();
-            let direction = line_split[0];
-            let distance: usize = line_split[1].parse().expect("Bad parse");
-
-            Direction::from((direction, distance))
+        .split('\n')
+        .enumerate()
+        .map(|(index, line)| {
+            let error = |column| ParseError::new(9, index + 1, column, line);
+            let (direction, distance) = line.split_once(' ').ok_or_else(|| error(1))?;
+            let distance: usize = distance.parse().map_err(|_| error(direction.len() + 2))?;
+
+            Direction::try_from((direction, distance)).map_err(|_| error(1))
         })
         .collect()
 }
 
 struct Grid {
-    knot_positions: Vec<(isize, isize)>,
-    num_knots: usize,
-    t_visited: HashSet<(isize, isize)>,
+    knot_positions: Vec<Vec2>,
+    t_visited: HashSet<Vec2>,
 }
 
 impl Grid {
     fn new(num_knots: usize) -> Self {
         Self {
-            knot_positions: vec![(0, 0); num_knots],
-            num_knots,
-            t_visited: HashSet::from([(0, 0)]),
+            knot_positions: vec![Vec2::ZERO; num_knots],
+            t_visited: HashSet::from([Vec2::ZERO]),
         }
     }
 
-    fn knot_pos(&self, knot_index: usize) -> &(isize, isize) {
-        &self.knot_positions[knot_index]
-    }
-
-    fn update_knot(&mut self, knot_index: usize, new_x: isize, new_y: isize) {
-        self.knot_positions[knot_index] = (new_x, new_y)
-    }
-
-    // Movement for knot x relative to knot x - 1
-    fn knot_movement(&self, tail_index: usize) -> (Option<Direction>, Option<Direction>) {
-        let (h_x, h_y) = self.knot_pos(tail_index - 1);
-        let (t_x, t_y) = self.knot_pos(tail_index);
-        let x_deviation = h_x - t_x;
-        let x_deviation_abs = x_deviation.abs();
-        let y_deviation = h_y - t_y;
-        let y_deviation_abs = y_deviation.abs();
-
-        let diagonal_deviation =
-            x_deviation_abs > 0 && y_deviation_abs > 0 && x_deviation_abs + y_deviation_abs >= 3;
-
-        let x_direction = if diagonal_deviation || x_deviation_abs > 1 {
-            match x_deviation.signum() {
-                -1 => Some(Direction::Left(1)),
-                1 => Some(Direction::Right(1)),
-                _ => panic!("Bad signum"),
-            }
-        } else {
-            None
-        };
-
-        let y_direction = if diagonal_deviation || y_deviation_abs > 1 {
-            match y_deviation.signum() {
-                -1 => Some(Direction::Down(1)),
-                1 => Some(Direction::Up(1)),
-                _ => panic!("Bad signum"),
-            }
-        } else {
-            None
-        };
-
-        // This is the correction value that will move the tail to the right position
-        (x_direction, y_direction)
-    }
-
-    fn move_knot(&mut self, knot_index: usize, direction: &Direction) {
-        let distance = direction.distance();
-
-        let (h_x, h_y) = self.knot_pos(knot_index);
-
-        if distance > 0 {
-            match direction {
-                Direction::Up(_) => {
-                    self.update_knot(knot_index, *h_x, h_y + 1);
-                }
-                Direction::Down(_) => {
-                    self.update_knot(knot_index, *h_x, h_y - 1);
-                }
-                Direction::Left(_) => {
-                    self.update_knot(knot_index, h_x - 1, *h_y);
-                }
-                Direction::Right(_) => {
-                    self.update_knot(knot_index, h_x + 1, *h_y);
-                }
-            }
+    fn move_head(&mut self, direction: &Direction) {
+        for _ in 0..direction.distance() {
+            self.knot_positions[0] += direction.unit();
 
-            for i in 1..self.num_knots {
+            for i in 1..self.knot_positions.len() {
                 self.adjust_knot(i);
             }
 
-            let distance_left = distance - 1;
-
-            let new_direction = match direction {
-                Direction::Up(_) => Direction::Up(distance_left),
-                Direction::Down(_) => Direction::Down(distance_left),
-                Direction::Left(_) => Direction::Left(distance_left),
-                Direction::Right(_) => Direction::Right(distance_left),
-            };
-            self.move_knot(knot_index, &new_direction)
-        };
-    }
-
-    fn adjust_knot(&mut self, knot_index: usize) {
-        match self.knot_movement(knot_index) {
-            (None, None) => {}
-            (None, Some(y_direction)) => self.move_tail(&y_direction, knot_index),
-            (Some(x_direction), None) => self.move_tail(&x_direction, knot_index),
-            (Some(x_direction), Some(y_direction)) => {
-                self.move_tail(&x_direction, knot_index);
-                self.move_tail(&y_direction, knot_index);
-            }
-        }
-
-        if knot_index == self.num_knots - 1 {
             self.visit();
         }
     }
 
-    fn move_tail(&mut self, direction: &Direction, knot_index: usize) {
-        let (t_x, t_y) = self.knot_pos(knot_index);
-        match direction {
-            Direction::Right(_) => self.update_knot(knot_index, t_x + 1, *t_y),
-            Direction::Left(_) => self.update_knot(knot_index, t_x - 1, *t_y),
-            Direction::Up(_) => self.update_knot(knot_index, *t_x, t_y + 1),
-            Direction::Down(_) => self.update_knot(knot_index, *t_x, t_y - 1),
+    // Moves knot x one step towards knot x - 1 if they're no longer touching
+    fn adjust_knot(&mut self, knot_index: usize) {
+        let head = self.knot_positions[knot_index - 1];
+        let tail = self.knot_positions[knot_index];
+
+        if head.chebyshev(tail) > 1 {
+            self.knot_positions[knot_index] += (head - tail).signum();
         }
     }
 
     fn visit(&mut self) {
-        let (t_x, t_y) = self.knot_pos(self.num_knots - 1);
-        self.t_visited.insert((*t_x, *t_y));
+        let tail = self.knot_positions[self.knot_positions.len() - 1];
+        self.t_visited.insert(tail);
     }
 }
 
 #[aoc(day9, part1)]
 fn part1(input: &[Direction]) -> usize {
     let mut grid = Grid::new(2);
-    input
-        .iter()
-        .for_each(|movement| grid.move_knot(0, movement));
+    input.iter().for_each(|movement| grid.move_head(movement));
 
     // visualize the path
     // let x_min_index = grid
     //     .t_visited
     //     .iter()
-    //     .min_by(|one, two| one.0.cmp(&two.0))
+    //     .min_by(|one, two| one.x.cmp(&two.x))
     //     .unwrap()
-    //     .0;
+    //     .x;
     // let x_max_index = grid
     //     .t_visited
     //     .iter()
-    //     .max_by(|one, two| one.0.cmp(&two.0))
+    //     .max_by(|one, two| one.x.cmp(&two.x))
     //     .unwrap()
-    //     .0;
+    //     .x;
     // let y_min_index = grid
     //     .t_visited
     //     .iter()
-    //     .min_by(|one, two| one.1.cmp(&two.1))
+    //     .min_by(|one, two| one.y.cmp(&two.y))
     //     .unwrap()
-    //     .1;
+    //     .y;
     // let y_max_index = grid
     //     .t_visited
     //     .iter()
-    //     .max_by(|one, two| one.1.cmp(&two.1))
+    //     .max_by(|one, two| one.y.cmp(&two.y))
     //     .unwrap()
-    //     .1;
-    // for y in (y_min_index..=y_max_index).rev() {
+    //     .y;
+    // for y in y_min_index..=y_max_index {
     //     for x in x_min_index..=x_max_index {
     //         if x == 0 && y == 0 {
     //             print!("s");
     //         } else {
-    //             if grid.t_visited.contains(&(x as isize, y as isize)) {
+    //             if grid.t_visited.contains(&Vec2::new(x, y)) {
     //                 print!("#")
     //             } else {
     //                 print!(".")
@@ -226,13 +153,31 @@ fn part1(input: &[Direction]) -> usize {
 #[aoc(day9, part2)]
 fn part2(input: &[Direction]) -> usize {
     let mut grid = Grid::new(10);
-    input
-        .iter()
-        .for_each(|movement| grid.move_knot(0, movement));
+    input.iter().for_each(|movement| grid.move_head(movement));
 
     grid.t_visited.len()
 }
 
+pub struct Day9;
+
+impl Solution for Day9 {
+    type Input = Vec<Direction>;
+    type Answer1 = usize;
+    type Answer2 = usize;
+
+    fn parse(input: &str) -> Result<Self::Input, ParseError> {
+        input_generator(input)
+    }
+
+    fn part1(input: &Self::Input) -> Self::Answer1 {
+        part1(input)
+    }
+
+    fn part2(input: &Self::Input) -> Self::Answer2 {
+        part2(input)
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -248,13 +193,13 @@ R 2";
 
     #[test]
     fn part1_test() {
-        let input = input_generator(INPUT_STR);
+        let input = input_generator(INPUT_STR).unwrap();
         assert_eq!(part1(&input), 13);
     }
 
     #[test]
     fn part2_test() {
-        let input = input_generator(INPUT_STR);
+        let input = input_generator(INPUT_STR).unwrap();
         assert_eq!(part2(&input), 1);
     }
 }
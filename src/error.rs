@@ -0,0 +1,97 @@
+use std::error::Error;
+use std::fmt::Display;
+
+/// A failure to parse a day's puzzle input, pointing at where it went wrong.
+///
+/// `line` and `column` are 1-based, and `snippet` is the full text of the offending line.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ParseError {
+    pub day: u32,
+    pub line: usize,
+    pub column: usize,
+    pub snippet: String,
+}
+
+impl ParseError {
+    pub fn new(day: u32, line: usize, column: usize, snippet: &str) -> Self {
+        Self {
+            day,
+            line,
+            column,
+            snippet: String::from(snippet),
+        }
+    }
+
+    /// Builds an error for a failure at the start of `remaining`, which must be a suffix of `input`
+    pub fn at(day: u32, input: &str, remaining: &str) -> Self {
+        // Separated lists stop before the separator, so the line that failed is the next one
+        let remaining = remaining.trim_start_matches('\n');
+        let offset = input.len() - remaining.len();
+        let consumed = &input[..offset];
+        let line_start = consumed.rfind('\n').map_or(0, |index| index + 1);
+        let line = consumed.matches('\n').count() + 1;
+        let column = consumed[line_start..].chars().count() + 1;
+        let snippet = input[line_start..].lines().next().unwrap_or("");
+
+        Self::new(day, line, column, snippet)
+    }
+
+    pub fn from_nom(day: u32, input: &str, err: nom::Err<nom::error::Error<&str>>) -> Self {
+        match err {
+            nom::Err::Error(e) | nom::Err::Failure(e) => Self::at(day, input, e.input),
+            nom::Err::Incomplete(_) => Self::at(day, input, ""),
+        }
+    }
+
+    /// A multi-line description with the offending line quoted and the column marked
+    pub fn report(&self) -> String {
+        let gutter = self.line.to_string();
+        let padding = " ".repeat(gutter.len());
+        let marker = " ".repeat(self.column.saturating_sub(1));
+
+        format!(
+            "day {}: parse error at line {}, column {}\n{padding} |\n{gutter} | {}\n{padding} | {marker}^",
+            self.day, self.line, self.column, self.snippet
+        )
+    }
+}
+
+impl Display for ParseError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "day {}: parse error at line {}, column {}: {:?}",
+            self.day, self.line, self.column, self.snippet
+        )
+    }
+}
+
+impl Error for ParseError {}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn at_test() {
+        let input = "1000\n2000\n3x00\n4000";
+        let error = ParseError::at(1, input, &input[11..]);
+        assert_eq!(error, ParseError::new(1, 3, 2, "3x00"));
+    }
+
+    #[test]
+    fn report_test() {
+        let error = ParseError::new(4, 12, 3, "2-x,4-5");
+        assert_eq!(
+            error.report(),
+            "day 4: parse error at line 12, column 3\n   |\n12 | 2-x,4-5\n   |   ^"
+        );
+    }
+
+    #[test]
+    fn at_end_test() {
+        let input = "1000\n2000";
+        let error = ParseError::at(1, input, "");
+        assert_eq!(error, ParseError::new(1, 2, 5, "2000"));
+    }
+}
@@ -0,0 +1,287 @@
+use std::fmt::Display;
+use std::ops::{Index, IndexMut};
+
+use crate::error::ParseError;
+use crate::vec2::Vec2;
+
+/// A rectangular 2D grid, stored row by row. Positions are [`Vec2`]s with `y` growing downwards.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Grid<T> {
+    width: usize,
+    height: usize,
+    cells: Vec<T>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum GridError {
+    Empty,
+    /// Row `row` (0-based) has a different length to the first row
+    RaggedRow {
+        row: usize,
+        expected: usize,
+        found: usize,
+    },
+}
+
+impl Display for GridError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            GridError::Empty => write!(f, "grid is empty"),
+            GridError::RaggedRow {
+                row,
+                expected,
+                found,
+            } => write!(f, "row {} has {found} cells, expected {expected}", row + 1),
+        }
+    }
+}
+
+impl std::error::Error for GridError {}
+
+impl<T> Grid<T> {
+    pub fn from_rows(rows: Vec<Vec<T>>) -> Result<Self, GridError> {
+        let width = rows.first().map_or(0, Vec::len);
+        if width == 0 {
+            return Err(GridError::Empty);
+        }
+
+        if let Some((row, cells)) = rows
+            .iter()
+            .enumerate()
+            .find(|(_, cells)| cells.len() != width)
+        {
+            return Err(GridError::RaggedRow {
+                row,
+                expected: width,
+                found: cells.len(),
+            });
+        }
+
+        let height = rows.len();
+        let cells = rows.into_iter().flatten().collect();
+        Ok(Self {
+            width,
+            height,
+            cells,
+        })
+    }
+
+    /// A `width` by `height` grid with every cell set to `value`. Like [`Grid::from_rows`], a grid
+    /// with no cells is rejected.
+    pub fn filled(width: usize, height: usize, value: T) -> Result<Self, GridError>
+    where
+        T: Clone,
+    {
+        if width == 0 || height == 0 {
+            return Err(GridError::Empty);
+        }
+
+        Ok(Self {
+            width,
+            height,
+            cells: vec![value; width * height],
+        })
+    }
+
+    /// Parses one cell per character, one row per line. Characters that `cell` rejects and
+    /// rows of the wrong length are reported as a [`ParseError`] for `day`.
+    pub fn parse(
+        day: u32,
+        input: &str,
+        cell: impl Fn(char) -> Option<T>,
+    ) -> Result<Self, ParseError> {
+        let lines: Vec<&str> = input.lines().collect();
+        let mut rows = Vec::with_capacity(lines.len());
+
+        for (y, line) in lines.iter().enumerate() {
+            let row = line
+                .chars()
+                .enumerate()
+                .map(|(x, c)| cell(c).ok_or_else(|| ParseError::new(day, y + 1, x + 1, line)))
+                .collect::<Result<Vec<T>, ParseError>>()?;
+            rows.push(row);
+        }
+
+        Self::from_rows(rows).map_err(|e| match e {
+            GridError::Empty => ParseError::new(day, 1, 1, ""),
+            GridError::RaggedRow {
+                row,
+                expected,
+                found,
+            } => ParseError::new(day, row + 1, expected.min(found) + 1, lines[row]),
+        })
+    }
+
+    pub fn width(&self) -> usize {
+        self.width
+    }
+
+    pub fn height(&self) -> usize {
+        self.height
+    }
+
+    pub fn contains(&self, position: Vec2) -> bool {
+        self.index_of(position).is_some()
+    }
+
+    fn index_of(&self, position: Vec2) -> Option<usize> {
+        let (x, y) = position.to_indices()?;
+        (x < self.width && y < self.height).then_some(y * self.width + x)
+    }
+
+    fn position_of(&self, index: usize) -> Vec2 {
+        Vec2::from_indices(index % self.width, index / self.width)
+    }
+
+    pub fn get(&self, position: Vec2) -> Option<&T> {
+        self.index_of(position).map(|index| &self.cells[index])
+    }
+
+    pub fn get_mut(&mut self, position: Vec2) -> Option<&mut T> {
+        self.index_of(position).map(|index| &mut self.cells[index])
+    }
+
+    /// Every position, row by row
+    pub fn positions(&self) -> impl Iterator<Item = Vec2> + '_ {
+        (0..self.cells.len()).map(|index| self.position_of(index))
+    }
+
+    /// Every cell with its position, row by row
+    pub fn iter(&self) -> impl Iterator<Item = (Vec2, &T)> {
+        self.cells
+            .iter()
+            .enumerate()
+            .map(|(index, cell)| (self.position_of(index), cell))
+    }
+
+    pub fn rows(&self) -> impl Iterator<Item = &[T]> {
+        self.cells.chunks(self.width)
+    }
+
+    /// Panics if `y` is out of bounds
+    pub fn row(&self, y: usize) -> &[T] {
+        &self.cells[y * self.width..(y + 1) * self.width]
+    }
+
+    /// Panics if `x` is out of bounds
+    pub fn column(&self, x: usize) -> impl Iterator<Item = &T> {
+        assert!(x < self.width, "column {x} out of bounds");
+        self.cells.iter().skip(x).step_by(self.width)
+    }
+
+    /// The cells seen walking from `from` in steps of `direction`, not including `from` itself,
+    /// until the edge of the grid
+    pub fn ray(&self, from: Vec2, direction: Vec2) -> impl Iterator<Item = (Vec2, &T)> {
+        let mut position = from;
+        std::iter::from_fn(move || {
+            position += direction;
+            self.get(position).map(|cell| (position, cell))
+        })
+    }
+
+    /// The in-bounds orthogonal neighbours of `position`
+    pub fn neighbors_4(&self, position: Vec2) -> impl Iterator<Item = (Vec2, &T)> {
+        position
+            .neighbors_4()
+            .filter_map(|neighbor| self.get(neighbor).map(|cell| (neighbor, cell)))
+    }
+
+    /// The in-bounds neighbours of `position`, including diagonals
+    pub fn neighbors_8(&self, position: Vec2) -> impl Iterator<Item = (Vec2, &T)> {
+        position
+            .neighbors_8()
+            .filter_map(|neighbor| self.get(neighbor).map(|cell| (neighbor, cell)))
+    }
+
+    pub fn map<U>(&self, f: impl Fn(&T) -> U) -> Grid<U> {
+        Grid {
+            width: self.width,
+            height: self.height,
+            cells: self.cells.iter().map(f).collect(),
+        }
+    }
+}
+
+impl<T> Index<Vec2> for Grid<T> {
+    type Output = T;
+
+    fn index(&self, position: Vec2) -> &Self::Output {
+        self.get(position)
+            .unwrap_or_else(|| panic!("{position} is outside the grid"))
+    }
+}
+
+impl<T> IndexMut<Vec2> for Grid<T> {
+    fn index_mut(&mut self, position: Vec2) -> &mut Self::Output {
+        self.get_mut(position)
+            .unwrap_or_else(|| panic!("{position} is outside the grid"))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn digits(input: &str) -> Grid<u32> {
+        Grid::parse(0, input, |c| c.to_digit(10)).unwrap()
+    }
+
+    #[test]
+    fn parse_test() {
+        let grid = digits("123\n456");
+        assert_eq!(grid.width(), 3);
+        assert_eq!(grid.height(), 2);
+        assert_eq!(grid[Vec2::new(2, 1)], 6);
+        assert_eq!(grid.get(Vec2::new(3, 0)), None);
+        assert_eq!(grid.get(Vec2::new(0, -1)), None);
+    }
+
+    #[test]
+    fn parse_error_test() {
+        let error = Grid::parse(8, "123\n4x6", |c| c.to_digit(10))
+            .err()
+            .unwrap();
+        assert_eq!(error, ParseError::new(8, 2, 2, "4x6"));
+
+        let error = Grid::parse(8, "123\n45\n789", |c| c.to_digit(10))
+            .err()
+            .unwrap();
+        assert_eq!(error, ParseError::new(8, 2, 3, "45"));
+    }
+
+    #[test]
+    fn filled_test() {
+        let grid = Grid::filled(3, 2, 0).unwrap();
+        assert_eq!(grid.rows().count(), 2);
+        assert_eq!(grid.positions().last(), Some(Vec2::new(2, 1)));
+        assert_eq!(Grid::filled(0, 2, 0), Err(GridError::Empty));
+        assert_eq!(Grid::filled(3, 0, 0), Err(GridError::Empty));
+    }
+
+    #[test]
+    fn rows_and_columns_test() {
+        let grid = digits("123\n456");
+        assert_eq!(grid.row(1), &[4, 5, 6]);
+        assert_eq!(grid.column(1).copied().collect::<Vec<_>>(), vec![2, 5]);
+        assert_eq!(grid.rows().count(), 2);
+    }
+
+    #[test]
+    fn ray_test() {
+        let grid = digits("123\n456\n789");
+        let left: Vec<u32> = grid
+            .ray(Vec2::new(2, 1), Vec2::LEFT)
+            .map(|(_, c)| *c)
+            .collect();
+        assert_eq!(left, vec![5, 4]);
+        assert_eq!(grid.ray(Vec2::new(1, 0), Vec2::UP).count(), 0);
+    }
+
+    #[test]
+    fn neighbors_test() {
+        let grid = digits("123\n456\n789");
+        assert_eq!(grid.neighbors_4(Vec2::new(0, 0)).count(), 2);
+        assert_eq!(grid.neighbors_8(Vec2::new(1, 1)).count(), 8);
+        assert_eq!(grid.neighbors_8(Vec2::new(2, 2)).count(), 3);
+    }
+}
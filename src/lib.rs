@@ -3,6 +3,8 @@ extern crate aoc_runner;
 #[macro_use]
 extern crate aoc_runner_derive;
 
+pub mod bench;
+pub mod cli;
 pub mod day1;
 pub mod day10;
 pub mod day11;
@@ -16,7 +18,14 @@ pub mod day6;
 pub mod day7;
 pub mod day8;
 pub mod day9;
+pub mod error;
+pub mod grid;
+pub mod ocr;
 pub mod parsers;
+pub mod scaffold;
+pub mod search;
+pub mod solution;
 pub mod vec2;
+pub mod verify;
 
 aoc_lib! { year = 2022 }
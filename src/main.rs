@@ -1,7 +1,20 @@
-extern crate advent_of_code_2022;
-extern crate aoc_runner;
-extern crate aoc_runner_derive;
+use std::env;
+use std::process::ExitCode;
 
-use aoc_runner_derive::aoc_main;
+use advent_of_code_2022::cli::{self, USAGE};
 
-aoc_main! { lib = advent_of_code_2022 }
+fn main() -> ExitCode {
+    match cli::parse_args(env::args().skip(1)) {
+        Ok(command) => {
+            if cli::execute(command) {
+                ExitCode::SUCCESS
+            } else {
+                ExitCode::FAILURE
+            }
+        }
+        Err(e) => {
+            eprintln!("error: {e}\n\n{USAGE}");
+            ExitCode::from(2)
+        }
+    }
+}
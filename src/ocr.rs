@@ -0,0 +1,93 @@
+use crate::grid::Grid;
+use crate::vec2::Vec2;
+
+pub const LETTER_WIDTH: usize = 4;
+pub const LETTER_HEIGHT: usize = 6;
+/// Letters are drawn with one blank column between them
+const LETTER_SPACING: usize = LETTER_WIDTH + 1;
+
+/// The block letters Advent of Code draws on screens, as far as they're known
+const GLYPHS: [(char, [&str; LETTER_HEIGHT]); 18] = [
+    ('A', [".##.", "#..#", "#..#", "####", "#..#", "#..#"]),
+    ('B', ["###.", "#..#", "###.", "#..#", "#..#", "###."]),
+    ('C', [".##.", "#..#", "#...", "#...", "#..#", ".##."]),
+    ('E', ["####", "#...", "###.", "#...", "#...", "####"]),
+    ('F', ["####", "#...", "###.", "#...", "#...", "#..."]),
+    ('G', [".##.", "#..#", "#...", "#.##", "#..#", ".###"]),
+    ('H', ["#..#", "#..#", "####", "#..#", "#..#", "#..#"]),
+    ('I', [".###", "..#.", "..#.", "..#.", "..#.", ".###"]),
+    ('J', ["..##", "...#", "...#", "...#", "#..#", ".##."]),
+    ('K', ["#..#", "#.#.", "##..", "#.#.", "#.#.", "#..#"]),
+    ('L', ["#...", "#...", "#...", "#...", "#...", "####"]),
+    ('O', [".##.", "#..#", "#..#", "#..#", "#..#", ".##."]),
+    ('P', ["###.", "#..#", "#..#", "###.", "#...", "#..."]),
+    ('R', ["###.", "#..#", "#..#", "###.", "#.#.", "#..#"]),
+    ('S', [".###", "#...", "#...", ".##.", "...#", "###."]),
+    ('U', ["#..#", "#..#", "#..#", "#..#", "#..#", ".##."]),
+    ('Y', ["#...", "#...", ".#.#", "..#.", "..#.", "..#."]),
+    ('Z', ["####", "...#", "..#.", ".#..", "#...", "####"]),
+];
+
+/// Whether the letter-sized block of `pixels` with its top left at `origin` matches `glyph`
+fn matches_glyph(pixels: &Grid<bool>, origin: Vec2, glyph: &[&str; LETTER_HEIGHT]) -> bool {
+    glyph.iter().enumerate().all(|(y, row)| {
+        row.chars().enumerate().all(|(x, c)| {
+            let lit = pixels
+                .get(origin + Vec2::from_indices(x, y))
+                .copied()
+                .unwrap_or(false);
+            lit == (c == '#')
+        })
+    })
+}
+
+/// Reads the letters drawn across the top of `pixels`, left to right. Anything that isn't a
+/// known letter comes out as `?`.
+pub fn read_letters(pixels: &Grid<bool>) -> String {
+    let count = (pixels.width() + 1) / LETTER_SPACING;
+
+    (0..count)
+        .map(|index| {
+            let origin = Vec2::from_indices(index * LETTER_SPACING, 0);
+            GLYPHS
+                .iter()
+                .find(|(_, glyph)| matches_glyph(pixels, origin, glyph))
+                .map_or('?', |(letter, _)| *letter)
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn screen(rows: &[&str]) -> Grid<bool> {
+        Grid::parse(0, &rows.join("\n"), |c| Some(c == '#')).unwrap()
+    }
+
+    #[test]
+    fn read_letters_test() {
+        let pixels = screen(&[
+            "#..#.####.#....#.....##..",
+            "#..#.#....#....#....#..#.",
+            "####.###..#....#....#..#.",
+            "#..#.#....#....#....#..#.",
+            "#..#.#....#....#....#..#.",
+            "#..#.####.####.####..##..",
+        ]);
+        assert_eq!(read_letters(&pixels), "HELLO");
+    }
+
+    #[test]
+    fn unknown_letter_test() {
+        let pixels = screen(&[
+            "####.#..#",
+            "####.#..#",
+            "####.####",
+            "####.#..#",
+            "####.#..#",
+            "####.#..#",
+        ]);
+        assert_eq!(read_letters(&pixels), "?H");
+    }
+}
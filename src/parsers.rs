@@ -1,7 +1,128 @@
-use nom::character::complete::digit1;
-use nom::combinator::map_res;
-use nom::IResult;
+use nom::bytes::complete::tag;
+use nom::character::complete::{char, digit1};
+use nom::combinator::{map_res, opt, recognize};
+use nom::error::Error;
+use nom::sequence::pair;
+use nom::{IResult, Parser};
+
+use crate::error::ParseError;
 
 pub fn parse_usize(input: &str) -> IResult<&str, usize> {
     map_res(digit1, str::parse)(input)
 }
+
+pub fn parse_isize(input: &str) -> IResult<&str, isize> {
+    map_res(recognize(pair(opt(char('-')), digit1)), str::parse)(input)
+}
+
+/// Like nom's `separated_list1`, except that an item which fails partway through is an error
+/// rather than the end of the list. The error then points at the typo itself instead of at the
+/// start of the line or block it's in. An item that fails on its very first character still
+/// ends the list, so whatever follows the list can be parsed as usual.
+pub fn separated_list1_cut<'a, O, S>(
+    mut separator: impl Parser<&'a str, S, Error<&'a str>>,
+    mut item: impl Parser<&'a str, O, Error<&'a str>>,
+) -> impl FnMut(&'a str) -> IResult<&'a str, Vec<O>> {
+    move |input| {
+        let (mut input, first) = item.parse(input)?;
+        let mut items = vec![first];
+
+        while let Ok((next, _)) = separator.parse(input) {
+            match item.parse(next) {
+                Ok((rest, output)) => {
+                    items.push(output);
+                    input = rest;
+                }
+                Err(nom::Err::Error(e)) if e.input.len() == next.len() => break,
+                Err(nom::Err::Error(e)) => return Err(nom::Err::Failure(e)),
+                Err(e) => return Err(e),
+            }
+        }
+
+        Ok((input, items))
+    }
+}
+
+/// One or more `item`s, one per line
+pub fn lines_of<'a, O>(
+    item: impl Parser<&'a str, O, Error<&'a str>>,
+) -> impl FnMut(&'a str) -> IResult<&'a str, Vec<O>> {
+    separated_list1_cut(char('\n'), item)
+}
+
+/// One or more `item`s separated by blank lines
+pub fn blocks_of<'a, O>(
+    item: impl Parser<&'a str, O, Error<&'a str>>,
+) -> impl FnMut(&'a str) -> IResult<&'a str, Vec<O>> {
+    separated_list1_cut(tag("\n\n"), item)
+}
+
+/// Runs `parser` over a whole day's input. Anything other than trailing newlines left over
+/// counts as an error, and nom's errors are turned into a [`ParseError`] pointing at the input.
+pub fn finish<'a, O>(
+    day: u32,
+    input: &'a str,
+    mut parser: impl Parser<&'a str, O, Error<&'a str>>,
+) -> Result<O, ParseError> {
+    match parser.parse(input) {
+        Ok((remaining, output)) if remaining.trim_end_matches('\n').is_empty() => Ok(output),
+        Ok((remaining, _)) => Err(ParseError::at(day, input, remaining)),
+        Err(e) => Err(ParseError::from_nom(day, input, e)),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use nom::character::complete::alpha1;
+
+    use super::*;
+
+    #[test]
+    fn parse_isize_test() {
+        assert_eq!(parse_isize("-15 rest"), Ok((" rest", -15)));
+        assert_eq!(parse_isize("42"), Ok(("", 42)));
+        assert!(parse_isize("-").is_err());
+    }
+
+    #[test]
+    fn lines_and_blocks_test() {
+        let input = "1\n2\n\n3";
+        assert_eq!(
+            finish(0, input, blocks_of(lines_of(parse_usize))),
+            Ok(vec![vec![1, 2], vec![3]])
+        );
+        assert_eq!(
+            finish(0, "1\n2\n\n3\n-x", blocks_of(lines_of(parse_isize))),
+            Err(ParseError::new(0, 5, 2, "-x"))
+        );
+    }
+
+    #[test]
+    fn separated_list1_cut_test() {
+        let mut list = separated_list1_cut(char('\n'), pair(parse_usize, alpha1));
+        assert_eq!(
+            list("1a\n2b\n\nrest"),
+            Ok(("\n\nrest", vec![(1, "a"), (2, "b")]))
+        );
+        assert_eq!(list("1a\nrest"), Ok(("\nrest", vec![(1, "a")])));
+        assert_eq!(
+            list("1a\n2-\n3c"),
+            Err(nom::Err::Failure(Error::new(
+                "-\n3c",
+                nom::error::ErrorKind::Alpha
+            )))
+        );
+    }
+
+    #[test]
+    fn finish_error_test() {
+        assert_eq!(
+            finish(8, "12\n3x", lines_of(parse_usize)),
+            Err(ParseError::new(8, 2, 2, "3x"))
+        );
+        assert_eq!(
+            finish(1, "1\n2\nthree", lines_of(parse_usize)),
+            Err(ParseError::new(1, 3, 1, "three"))
+        );
+    }
+}
@@ -0,0 +1,226 @@
+use std::error::Error;
+use std::fmt::Display;
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+
+const TEMPLATE: &str = include_str!("template.rs");
+const PLACEHOLDER: &str = "__DAY__";
+
+#[derive(Debug)]
+pub enum ScaffoldError {
+    AlreadyExists(PathBuf),
+    /// The file exists but doesn't contain what we need to edit
+    Unrecognised(PathBuf),
+    Io(PathBuf, io::Error),
+}
+
+impl Display for ScaffoldError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ScaffoldError::AlreadyExists(path) => write!(f, "{} already exists", path.display()),
+            ScaffoldError::Unrecognised(path) => {
+                write!(
+                    f,
+                    "couldn't find where to register the day in {}",
+                    path.display()
+                )
+            }
+            ScaffoldError::Io(path, e) => write!(f, "{}: {e}", path.display()),
+        }
+    }
+}
+
+impl Error for ScaffoldError {}
+
+/// The template with every placeholder replaced by `day`, minus the comment explaining the
+/// placeholder
+pub fn render_template(day: u32) -> String {
+    TEMPLATE
+        .lines()
+        .skip_while(|line| line.starts_with("//"))
+        .map(|line| line.replace(PLACEHOLDER, &day.to_string()) + "\n")
+        .collect()
+}
+
+/// Inserts `line` among the lines of `source` matching `is_entry`, sorted by `key`.
+/// Returns `None` if there are no matching lines to anchor on.
+fn insert_sorted<K: Ord>(
+    source: &str,
+    line: &str,
+    is_entry: impl Fn(&str) -> bool,
+    key: impl Fn(&str) -> K,
+) -> Option<String> {
+    let lines: Vec<&str> = source.lines().collect();
+    let first = lines.iter().position(|l| is_entry(l))?;
+    let count = lines[first..].iter().take_while(|l| is_entry(l)).count();
+
+    let mut entries: Vec<&str> = lines[first..first + count].to_vec();
+    entries.push(line);
+    entries.sort_by_key(|entry| key(entry));
+    entries.dedup();
+
+    let mut result: Vec<&str> = lines[..first].to_vec();
+    result.extend(entries);
+    result.extend(&lines[first + count..]);
+    Some(result.join("\n") + "\n")
+}
+
+/// Adds `pub mod dayN;` to lib.rs, keeping the module list in rustfmt's order
+pub fn register_module(lib_rs: &str, day: u32) -> Option<String> {
+    insert_sorted(
+        lib_rs,
+        &format!("pub mod day{day};"),
+        |line| line.starts_with("pub mod "),
+        |line: &str| String::from(line.trim_start_matches("pub mod ").trim_end_matches(';')),
+    )
+}
+
+/// Adds the day's `Solution` to `solution::registry`, keeping it ordered by day
+pub fn register_solution(solution_rs: &str, day: u32) -> Option<String> {
+    insert_sorted(
+        solution_rs,
+        &format!("    registry.insert({day}, Box::new(crate::day{day}::Day{day}));"),
+        |line| line.starts_with("    registry.insert("),
+        |line| {
+            line.trim_start_matches("    registry.insert(")
+                .split(',')
+                .next()
+                .and_then(|day| day.parse::<u32>().ok())
+        },
+    )
+}
+
+fn read(path: &Path) -> Result<String, ScaffoldError> {
+    fs::read_to_string(path).map_err(|e| ScaffoldError::Io(path.to_path_buf(), e))
+}
+
+fn write(path: &Path, contents: &str) -> Result<(), ScaffoldError> {
+    fs::write(path, contents).map_err(|e| ScaffoldError::Io(path.to_path_buf(), e))
+}
+
+/// Creates `src/dayN.rs` and an empty `input/2022/dayN.txt` under `root`, and registers the day.
+/// Returns every path that was written.
+pub fn new_day(root: &Path, day: u32) -> Result<Vec<PathBuf>, ScaffoldError> {
+    let source_path = root.join(format!("src/day{day}.rs"));
+    let input_path = root.join(format!("input/2022/day{day}.txt"));
+    let lib_path = root.join("src/lib.rs");
+    let solution_path = root.join("src/solution.rs");
+
+    if source_path.exists() {
+        return Err(ScaffoldError::AlreadyExists(source_path));
+    }
+
+    // Work out every edit before writing anything, so a failure doesn't leave a half-registered day
+    let lib_rs = register_module(&read(&lib_path)?, day)
+        .ok_or_else(|| ScaffoldError::Unrecognised(lib_path.clone()))?;
+    let solution_rs = register_solution(&read(&solution_path)?, day)
+        .ok_or_else(|| ScaffoldError::Unrecognised(solution_path.clone()))?;
+
+    write(&source_path, &render_template(day))?;
+    write(&lib_path, &lib_rs)?;
+    write(&solution_path, &solution_rs)?;
+
+    let mut written = vec![source_path, lib_path, solution_path];
+    if !input_path.exists() {
+        if let Some(parent) = input_path.parent() {
+            fs::create_dir_all(parent).map_err(|e| ScaffoldError::Io(parent.to_path_buf(), e))?;
+        }
+        write(&input_path, "")?;
+        written.push(input_path);
+    }
+
+    Ok(written)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn render_template_test() {
+        let source = render_template(14);
+        assert!(source.starts_with("use crate::error::ParseError;"));
+        assert!(source.contains("#[aoc_generator(day14)]"));
+        assert!(source.contains("#[aoc(day14, part2)]"));
+        assert!(source.contains("impl Solution for Day14 {"));
+        assert!(!source.contains(PLACEHOLDER));
+    }
+
+    #[test]
+    fn register_module_test() {
+        let lib_rs = concat!(
+            "extern crate aoc_runner;\n\n",
+            "pub mod day1;\npub mod day13;\npub mod day2;\npub mod error;\n\n",
+            "aoc_lib! { year = 2022 }\n",
+        );
+        assert_eq!(
+            register_module(lib_rs, 14).unwrap(),
+            concat!(
+                "extern crate aoc_runner;\n\n",
+                "pub mod day1;\npub mod day13;\npub mod day14;\npub mod day2;\npub mod error;\n\n",
+                "aoc_lib! { year = 2022 }\n",
+            )
+        );
+    }
+
+    #[test]
+    fn register_solution_test() {
+        let solution_rs = concat!(
+            "    let mut registry: Registry = BTreeMap::new();\n",
+            "    registry.insert(9, Box::new(crate::day9::Day9));\n",
+            "    registry.insert(13, Box::new(crate::day13::Day13));\n",
+            "    registry\n",
+        );
+        assert_eq!(
+            register_solution(solution_rs, 10).unwrap(),
+            concat!(
+                "    let mut registry: Registry = BTreeMap::new();\n",
+                "    registry.insert(9, Box::new(crate::day9::Day9));\n",
+                "    registry.insert(10, Box::new(crate::day10::Day10));\n",
+                "    registry.insert(13, Box::new(crate::day13::Day13));\n",
+                "    registry\n",
+            )
+        );
+        assert_eq!(register_solution("fn main() {}\n", 10), None);
+    }
+
+    #[test]
+    fn new_day_test() {
+        // Scaffold into a copy of this crate's own lib.rs and solution.rs, so the test also
+        // catches them losing the lines that new_day anchors its edits on
+        let root = std::env::temp_dir().join(format!("aoc-new-day-{}", std::process::id()));
+        let src = root.join("src");
+        fs::create_dir_all(&src).unwrap();
+        write(&src.join("lib.rs"), include_str!("lib.rs")).unwrap();
+        write(&src.join("solution.rs"), include_str!("solution.rs")).unwrap();
+
+        let written = new_day(&root, 14).unwrap();
+        let input_path = root.join("input/2022/day14.txt");
+        assert_eq!(
+            written,
+            vec![
+                src.join("day14.rs"),
+                src.join("lib.rs"),
+                src.join("solution.rs"),
+                input_path.clone(),
+            ]
+        );
+
+        assert_eq!(read(&src.join("day14.rs")).unwrap(), render_template(14));
+        assert_eq!(read(&input_path).unwrap(), "");
+        let lib_rs = read(&src.join("lib.rs")).unwrap();
+        assert!(lib_rs.contains("pub mod day13;\npub mod day14;\npub mod day2;\n"));
+        let solution_rs = read(&src.join("solution.rs")).unwrap();
+        assert!(solution_rs.contains(concat!(
+            "    registry.insert(13, Box::new(crate::day13::Day13));\n",
+            "    registry.insert(14, Box::new(crate::day14::Day14));\n",
+        )));
+
+        let again = new_day(&root, 14);
+        fs::remove_dir_all(&root).unwrap();
+        assert!(
+            matches!(again, Err(ScaffoldError::AlreadyExists(path)) if path == src.join("day14.rs"))
+        );
+    }
+}
@@ -0,0 +1,276 @@
+use std::cmp::Reverse;
+use std::collections::{BinaryHeap, HashMap, VecDeque};
+use std::hash::Hash;
+
+use crate::grid::Grid;
+use crate::vec2::Vec2;
+
+/// Everything a search reached: the cost of the cheapest route to each node, and the node it
+/// was reached from along that route.
+#[derive(Debug, Clone)]
+pub struct Search<N> {
+    pub distances: HashMap<N, usize>,
+    pub predecessors: HashMap<N, N>,
+}
+
+impl<N: Clone + Eq + Hash> Search<N> {
+    fn from_sources(sources: impl IntoIterator<Item = N>) -> Self {
+        Self {
+            distances: sources.into_iter().map(|source| (source, 0)).collect(),
+            predecessors: HashMap::new(),
+        }
+    }
+
+    pub fn distance(&self, node: &N) -> Option<usize> {
+        self.distances.get(node).copied()
+    }
+
+    pub fn reached(&self, node: &N) -> bool {
+        self.distances.contains_key(node)
+    }
+
+    /// The route from whichever source was closest to `target`, including both ends.
+    /// `None` if `target` wasn't reached.
+    pub fn path_to(&self, target: &N) -> Option<Vec<N>> {
+        if !self.reached(target) {
+            return None;
+        }
+
+        let mut path = vec![target.clone()];
+        while let Some(previous) = self.predecessors.get(path.last()?) {
+            path.push(previous.clone());
+        }
+        path.reverse();
+        Some(path)
+    }
+}
+
+/// A [`Search`] over the cells of a grid, stored as dense grids rather than hash maps so that
+/// searching very large grids stays fast
+#[derive(Debug, Clone)]
+pub struct GridSearch {
+    pub distances: Grid<Option<usize>>,
+    pub predecessors: Grid<Option<Vec2>>,
+}
+
+impl GridSearch {
+    pub fn distance(&self, cell: &Vec2) -> Option<usize> {
+        self.distances.get(*cell).copied().flatten()
+    }
+
+    pub fn reached(&self, cell: &Vec2) -> bool {
+        self.distance(cell).is_some()
+    }
+
+    /// The route from whichever source was closest to `target`, including both ends.
+    /// `None` if `target` wasn't reached.
+    pub fn path_to(&self, target: &Vec2) -> Option<Vec<Vec2>> {
+        if !self.reached(target) {
+            return None;
+        }
+
+        let mut path = vec![*target];
+        while let Some(previous) = self.predecessors[*path.last()?] {
+            path.push(previous);
+        }
+        path.reverse();
+        Some(path)
+    }
+}
+
+/// [`dijkstra`] over the cells of `grid`. Only the grid's size is used; `neighbors` decides
+/// which steps are allowed.
+pub fn grid_dijkstra<T, I>(
+    grid: &Grid<T>,
+    sources: impl IntoIterator<Item = Vec2>,
+    mut neighbors: impl FnMut(Vec2) -> I,
+) -> GridSearch
+where
+    I: IntoIterator<Item = (Vec2, usize)>,
+{
+    let mut search = GridSearch {
+        distances: grid.map(|_| None),
+        predecessors: grid.map(|_| None),
+    };
+    let mut queue = BinaryHeap::new();
+    for source in sources {
+        search.distances[source] = Some(0);
+        queue.push(Reverse((0, source)));
+    }
+
+    while let Some(Reverse((distance, cell))) = queue.pop() {
+        if search.distances[cell] < Some(distance) {
+            // A cheaper route to this cell was found after this entry was queued
+            continue;
+        }
+
+        for (next, cost) in neighbors(cell) {
+            let next_distance = distance + cost;
+            if search.distances[next].is_none_or(|known| next_distance < known) {
+                search.distances[next] = Some(next_distance);
+                search.predecessors[next] = Some(cell);
+                queue.push(Reverse((next_distance, next)));
+            }
+        }
+    }
+
+    search
+}
+
+/// Breadth-first search where every step costs 1
+pub fn bfs<N, I>(
+    sources: impl IntoIterator<Item = N>,
+    mut neighbors: impl FnMut(&N) -> I,
+) -> Search<N>
+where
+    N: Clone + Eq + Hash,
+    I: IntoIterator<Item = N>,
+{
+    let mut search = Search::from_sources(sources);
+    let mut queue: VecDeque<N> = search.distances.keys().cloned().collect();
+
+    while let Some(node) = queue.pop_front() {
+        let distance = search.distances[&node] + 1;
+        for next in neighbors(&node) {
+            if !search.reached(&next) {
+                search.distances.insert(next.clone(), distance);
+                search.predecessors.insert(next.clone(), node.clone());
+                queue.push_back(next);
+            }
+        }
+    }
+
+    search
+}
+
+/// Shortest paths where `neighbors` gives the cost of each step
+pub fn dijkstra<N, I>(
+    sources: impl IntoIterator<Item = N>,
+    neighbors: impl FnMut(&N) -> I,
+) -> Search<N>
+where
+    N: Clone + Eq + Hash + Ord,
+    I: IntoIterator<Item = (N, usize)>,
+{
+    astar(sources, |_| false, neighbors, |_| 0)
+}
+
+/// Dijkstra guided towards a goal by `heuristic`, which must never overestimate the remaining
+/// cost. Stops as soon as a node matching `is_goal` is settled, so only that node's distance and
+/// path are guaranteed to be the cheapest.
+pub fn astar<N, I>(
+    sources: impl IntoIterator<Item = N>,
+    is_goal: impl Fn(&N) -> bool,
+    mut neighbors: impl FnMut(&N) -> I,
+    heuristic: impl Fn(&N) -> usize,
+) -> Search<N>
+where
+    N: Clone + Eq + Hash + Ord,
+    I: IntoIterator<Item = (N, usize)>,
+{
+    let mut search = Search::from_sources(sources);
+    let mut queue: BinaryHeap<Reverse<(usize, usize, N)>> = search
+        .distances
+        .keys()
+        .map(|source| Reverse((heuristic(source), 0, source.clone())))
+        .collect();
+
+    while let Some(Reverse((_, distance, node))) = queue.pop() {
+        if search.distances[&node] < distance {
+            // A cheaper route to this node was found after this entry was queued
+            continue;
+        }
+        if is_goal(&node) {
+            break;
+        }
+
+        for (next, cost) in neighbors(&node) {
+            let next_distance = distance + cost;
+            if search
+                .distance(&next)
+                .is_none_or(|known| next_distance < known)
+            {
+                search.distances.insert(next.clone(), next_distance);
+                search.predecessors.insert(next.clone(), node.clone());
+                queue.push(Reverse((
+                    next_distance + heuristic(&next),
+                    next_distance,
+                    next,
+                )));
+            }
+        }
+    }
+
+    search
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::vec2::Vec2;
+
+    /// A 5x5 room with a wall down the middle, open only at the bottom
+    fn open(position: &Vec2) -> bool {
+        (0..5).contains(&position.x)
+            && (0..5).contains(&position.y)
+            && !(position.x == 2 && position.y < 4)
+    }
+
+    fn steps(position: &Vec2) -> Vec<Vec2> {
+        position.neighbors_4().filter(open).collect()
+    }
+
+    #[test]
+    fn bfs_test() {
+        let search = bfs([Vec2::new(0, 0)], steps);
+        let target = Vec2::new(4, 0);
+        assert_eq!(search.distance(&target), Some(12));
+
+        let path = search.path_to(&target).unwrap();
+        assert_eq!(path.len(), 13);
+        assert_eq!(path[0], Vec2::new(0, 0));
+        assert!(path.windows(2).all(|step| step[0].manhattan(step[1]) == 1));
+        assert!(!search.reached(&Vec2::new(2, 0)));
+        assert_eq!(search.path_to(&Vec2::new(2, 0)), None);
+    }
+
+    #[test]
+    fn dijkstra_test() {
+        // Moving right is free, everything else costs 10
+        let weighted = |position: &Vec2| {
+            steps(position)
+                .into_iter()
+                .map(|next| (next, if next.x > position.x { 0 } else { 10 }))
+                .collect::<Vec<_>>()
+        };
+        let search = dijkstra([Vec2::new(0, 0)], weighted);
+        assert_eq!(search.distance(&Vec2::new(4, 4)), Some(40));
+        assert_eq!(search.distance(&Vec2::new(4, 0)), Some(80));
+    }
+
+    #[test]
+    fn grid_dijkstra_test() {
+        let grid = Grid::filled(5, 5, ()).unwrap();
+        let search = grid_dijkstra(&grid, [Vec2::new(0, 0)], |cell| {
+            steps(&cell).into_iter().map(|next| (next, 1))
+        });
+        let target = Vec2::new(4, 0);
+        assert_eq!(search.distance(&target), Some(12));
+        assert_eq!(search.path_to(&target).unwrap().len(), 13);
+        assert!(!search.reached(&Vec2::new(2, 0)));
+    }
+
+    #[test]
+    fn astar_test() {
+        let goal = Vec2::new(4, 0);
+        let unit = |position: &Vec2| steps(position).into_iter().map(|next| (next, 1));
+        let search = astar(
+            [Vec2::new(0, 0)],
+            |position| *position == goal,
+            unit,
+            |position| position.manhattan(goal),
+        );
+        assert_eq!(search.distance(&goal), Some(12));
+        assert_eq!(search.path_to(&goal).unwrap().len(), 13);
+    }
+}
@@ -0,0 +1,117 @@
+use std::collections::BTreeMap;
+use std::fmt::Display;
+
+use crate::error::ParseError;
+
+/// A single day's puzzle, callable without going through aoc-runner
+pub trait Solution {
+    type Input;
+    type Answer1: Display;
+    type Answer2: Display;
+
+    fn parse(input: &str) -> Result<Self::Input, ParseError>;
+
+    fn part1(input: &Self::Input) -> Self::Answer1;
+
+    fn part2(input: &Self::Input) -> Self::Answer2;
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum Part {
+    One,
+    Two,
+}
+
+impl Part {
+    pub const ALL: [Part; 2] = [Part::One, Part::Two];
+}
+
+impl Display for Part {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Part::One => write!(f, "1"),
+            Part::Two => write!(f, "2"),
+        }
+    }
+}
+
+/// Object-safe view of a [`Solution`], so days with different input types can live in one registry
+pub trait Solver {
+    fn parse(&self, input: &str) -> Result<Box<dyn Parsed>, ParseError>;
+}
+
+/// A day's parsed input, ready to have either part run against it
+pub trait Parsed {
+    fn part1(&self) -> String;
+
+    fn part2(&self) -> String;
+
+    fn run(&self, part: Part) -> String {
+        match part {
+            Part::One => self.part1(),
+            Part::Two => self.part2(),
+        }
+    }
+}
+
+struct ParsedInput<S: Solution>(S::Input);
+
+impl<S: Solution> Parsed for ParsedInput<S> {
+    fn part1(&self) -> String {
+        S::part1(&self.0).to_string()
+    }
+
+    fn part2(&self) -> String {
+        S::part2(&self.0).to_string()
+    }
+}
+
+impl<S: Solution + 'static> Solver for S {
+    fn parse(&self, input: &str) -> Result<Box<dyn Parsed>, ParseError> {
+        let parsed: ParsedInput<S> = ParsedInput(S::parse(input)?);
+        Ok(Box::new(parsed))
+    }
+}
+
+pub type Registry = BTreeMap<u32, Box<dyn Solver>>;
+
+/// Every implemented day, keyed by day number
+pub fn registry() -> Registry {
+    let mut registry: Registry = BTreeMap::new();
+    registry.insert(1, Box::new(crate::day1::Day1));
+    registry.insert(2, Box::new(crate::day2::Day2));
+    registry.insert(3, Box::new(crate::day3::Day3));
+    registry.insert(4, Box::new(crate::day4::Day4));
+    registry.insert(5, Box::new(crate::day5::Day5));
+    registry.insert(6, Box::new(crate::day6::Day6));
+    registry.insert(7, Box::new(crate::day7::Day7));
+    registry.insert(8, Box::new(crate::day8::Day8));
+    registry.insert(9, Box::new(crate::day9::Day9));
+    registry.insert(10, Box::new(crate::day10::Day10));
+    registry.insert(11, Box::new(crate::day11::Day11));
+    registry.insert(12, Box::new(crate::day12::Day12));
+    registry.insert(13, Box::new(crate::day13::Day13));
+    registry
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn registry_test() {
+        let registry = registry();
+        assert!((1..=13).all(|day| registry.contains_key(&day)));
+
+        let parsed = registry[&1].parse("1000\n2000\n\n4000").unwrap();
+        assert_eq!(parsed.part1(), "4000");
+        assert_eq!(parsed.part2(), "7000");
+    }
+
+    #[test]
+    fn registry_parse_error_test() {
+        let registry = registry();
+        let error = registry[&4].parse("2-4,6-8\n2-x,4-5").err().unwrap();
+        assert_eq!(error, ParseError::new(4, 2, 3, "2-x,4-5"));
+    }
+}
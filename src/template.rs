@@ -1,18 +1,42 @@
-#[aoc_generator(day1)]
-fn input_generator(input: &str) -> usize {
-    1
+// Template for `new-day`: every `__DAY__` is replaced with the day number
+use crate::error::ParseError;
+use crate::solution::Solution;
+
+#[aoc_generator(day__DAY__)]
+fn input_generator(_input: &str) -> Result<usize, ParseError> {
+    Ok(1)
 }
 
-#[aoc(day1, part1)]
-fn part1(input: &usize) -> usize {
+#[aoc(day__DAY__, part1)]
+fn part1(_input: &usize) -> usize {
     1
 }
 
-#[aoc(day1, part2)]
-fn part2(input: &usize) -> usize {
+#[aoc(day__DAY__, part2)]
+fn part2(_input: &usize) -> usize {
     1
 }
 
+pub struct Day__DAY__;
+
+impl Solution for Day__DAY__ {
+    type Input = usize;
+    type Answer1 = usize;
+    type Answer2 = usize;
+
+    fn parse(input: &str) -> Result<Self::Input, ParseError> {
+        input_generator(input)
+    }
+
+    fn part1(input: &Self::Input) -> Self::Answer1 {
+        part1(input)
+    }
+
+    fn part2(input: &Self::Input) -> Self::Answer2 {
+        part2(input)
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -21,13 +45,13 @@ mod tests {
 
     #[test]
     fn part1_test() {
-        let input = input_generator(INPUT_STR);
+        let input = input_generator(INPUT_STR).unwrap();
         assert_eq!(part1(&input), 1);
     }
 
     #[test]
     fn part2_test() {
-        let input = input_generator(INPUT_STR);
+        let input = input_generator(INPUT_STR).unwrap();
         assert_eq!(part2(&input), 1);
     }
 }
@@ -1,8 +1,97 @@
-use std::ops::{Add, AddAssign, Mul, Sub, SubAssign};
+use std::fmt::Display;
+use std::ops::{Add, AddAssign, Mul, Neg, Sub, SubAssign};
 
+/// A 2D integer vector. Grid-style coordinates are used throughout, so `y` grows downwards
+/// and [`Vec2::UP`] is `(0, -1)`.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash, PartialOrd, Ord)]
 pub struct Vec2 {
-    x: isize,
-    y: isize,
+    pub x: isize,
+    pub y: isize,
+}
+
+impl Vec2 {
+    pub const ZERO: Self = Self::new(0, 0);
+    pub const UP: Self = Self::new(0, -1);
+    pub const DOWN: Self = Self::new(0, 1);
+    pub const LEFT: Self = Self::new(-1, 0);
+    pub const RIGHT: Self = Self::new(1, 0);
+
+    /// The four orthogonal unit directions, clockwise from up
+    pub const DIRECTIONS_4: [Self; 4] = [Self::UP, Self::RIGHT, Self::DOWN, Self::LEFT];
+
+    /// All eight unit directions including diagonals, clockwise from up
+    pub const DIRECTIONS_8: [Self; 8] = [
+        Self::UP,
+        Self::new(1, -1),
+        Self::RIGHT,
+        Self::new(1, 1),
+        Self::DOWN,
+        Self::new(-1, 1),
+        Self::LEFT,
+        Self::new(-1, -1),
+    ];
+
+    pub const fn new(x: isize, y: isize) -> Self {
+        Self { x, y }
+    }
+
+    /// Converts grid indices into a vector. Panics if they don't fit in an `isize`.
+    pub fn from_indices(x: usize, y: usize) -> Self {
+        Self::new(
+            isize::try_from(x).expect("x index too large"),
+            isize::try_from(y).expect("y index too large"),
+        )
+    }
+
+    /// Converts back into grid indices, or `None` if either component is negative
+    pub fn to_indices(self) -> Option<(usize, usize)> {
+        Some((usize::try_from(self.x).ok()?, usize::try_from(self.y).ok()?))
+    }
+
+    pub fn manhattan(self, other: Self) -> usize {
+        self.x.abs_diff(other.x) + self.y.abs_diff(other.y)
+    }
+
+    pub fn chebyshev(self, other: Self) -> usize {
+        self.x.abs_diff(other.x).max(self.y.abs_diff(other.y))
+    }
+
+    /// Each component clamped to -1, 0 or 1
+    pub fn signum(self) -> Self {
+        Self::new(self.x.signum(), self.y.signum())
+    }
+
+    /// Rotates 90 degrees clockwise, as seen on a grid with `y` growing downwards
+    pub fn rotate_right(self) -> Self {
+        Self::new(-self.y, self.x)
+    }
+
+    /// Rotates 90 degrees anticlockwise, as seen on a grid with `y` growing downwards
+    pub fn rotate_left(self) -> Self {
+        Self::new(self.y, -self.x)
+    }
+
+    /// The four orthogonally adjacent positions, clockwise from up
+    pub fn neighbors_4(self) -> impl Iterator<Item = Self> {
+        Self::DIRECTIONS_4.into_iter().map(move |d| self + d)
+    }
+
+    /// All eight adjacent positions including diagonals, clockwise from up
+    pub fn neighbors_8(self) -> impl Iterator<Item = Self> {
+        Self::DIRECTIONS_8.into_iter().map(move |d| self + d)
+    }
+}
+
+impl From<(isize, isize)> for Vec2 {
+    fn from((x, y): (isize, isize)) -> Self {
+        Self::new(x, y)
+    }
+}
+
+impl Display for Vec2 {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "({}, {})", self.x, self.y)
+    }
 }
 
 impl Add for Vec2 {
@@ -51,3 +140,62 @@ impl Mul<isize> for Vec2 {
         }
     }
 }
+
+impl Neg for Vec2 {
+    type Output = Self;
+
+    fn neg(self) -> Self::Output {
+        Self::Output {
+            x: -self.x,
+            y: -self.y,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn distance_test() {
+        let a = Vec2::new(1, -2);
+        let b = Vec2::new(-3, 4);
+        assert_eq!(a.manhattan(b), 10);
+        assert_eq!(a.chebyshev(b), 6);
+        assert_eq!((b - a).signum(), Vec2::new(-1, 1));
+    }
+
+    #[test]
+    fn rotate_test() {
+        assert_eq!(Vec2::UP.rotate_right(), Vec2::RIGHT);
+        assert_eq!(Vec2::RIGHT.rotate_right(), Vec2::DOWN);
+        assert_eq!(Vec2::UP.rotate_left(), Vec2::LEFT);
+        assert_eq!(
+            Vec2::new(2, 1).rotate_left().rotate_right(),
+            Vec2::new(2, 1)
+        );
+    }
+
+    #[test]
+    fn neighbors_test() {
+        let origin = Vec2::new(5, 5);
+        let neighbors: Vec<Vec2> = origin.neighbors_4().collect();
+        assert_eq!(
+            neighbors,
+            vec![
+                Vec2::new(5, 4),
+                Vec2::new(6, 5),
+                Vec2::new(5, 6),
+                Vec2::new(4, 5)
+            ]
+        );
+        assert!(origin.neighbors_8().all(|n| n.chebyshev(origin) == 1));
+        assert_eq!(origin.neighbors_8().count(), 8);
+    }
+
+    #[test]
+    fn indices_test() {
+        assert_eq!(Vec2::from_indices(3, 4).to_indices(), Some((3, 4)));
+        assert_eq!(Vec2::new(-1, 4).to_indices(), None);
+    }
+}
@@ -0,0 +1,186 @@
+use std::collections::BTreeMap;
+use std::error::Error;
+use std::fmt::Display;
+
+use crate::solution::{Part, Solver};
+
+/// Expected answers, keyed by day and part
+pub type Answers = BTreeMap<(u32, Part), String>;
+
+/// A malformed line in an answers file
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct AnswersError {
+    pub line: usize,
+    pub snippet: String,
+}
+
+impl Display for AnswersError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "bad answers entry at line {}: {:?}, expected \"<day> <part> <answer>\"",
+            self.line, self.snippet
+        )
+    }
+}
+
+impl Error for AnswersError {}
+
+/// Parses lines of `<day> <part> <answer>`. Blank lines and lines starting with `#` are skipped.
+pub fn parse_answers(input: &str) -> Result<Answers, AnswersError> {
+    let mut answers = Answers::new();
+
+    for (index, line) in input.lines().enumerate() {
+        let trimmed = line.trim();
+        if trimmed.is_empty() || trimmed.starts_with('#') {
+            continue;
+        }
+
+        let error = || AnswersError {
+            line: index + 1,
+            snippet: String::from(line),
+        };
+        let mut fields = trimmed.splitn(3, char::is_whitespace);
+        let day = fields.next().and_then(|day| day.parse().ok());
+        let part = match fields.next() {
+            Some("1") => Some(Part::One),
+            Some("2") => Some(Part::Two),
+            _ => None,
+        };
+        let answer = fields
+            .next()
+            .map(str::trim)
+            .filter(|answer| !answer.is_empty());
+
+        match (day, part, answer) {
+            (Some(day), Some(part), Some(answer)) => {
+                answers.insert((day, part), String::from(answer));
+            }
+            _ => return Err(error()),
+        }
+    }
+
+    Ok(answers)
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Outcome {
+    Pass,
+    Fail {
+        expected: String,
+        actual: String,
+    },
+    /// There's no recorded answer to compare against
+    Missing {
+        actual: String,
+    },
+    /// The day couldn't be run at all, e.g. its input didn't parse
+    Error(String),
+}
+
+impl Display for Outcome {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Outcome::Pass => write!(f, "pass"),
+            Outcome::Fail { expected, actual } => {
+                write!(f, "FAIL (expected {expected}, got {actual})")
+            }
+            Outcome::Missing { actual } => write!(f, "missing (got {actual})"),
+            Outcome::Error(message) => write!(f, "ERROR ({message})"),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Check {
+    pub day: u32,
+    pub part: Part,
+    pub outcome: Outcome,
+}
+
+impl Display for Check {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "Day {} - Part {}: {}", self.day, self.part, self.outcome)
+    }
+}
+
+/// Marks both parts of a day as errored, for when it can't be run
+pub fn error_checks(day: u32, message: &str) -> Vec<Check> {
+    Part::ALL
+        .iter()
+        .map(|part| Check {
+            day,
+            part: *part,
+            outcome: Outcome::Error(String::from(message)),
+        })
+        .collect()
+}
+
+/// Runs both parts of a day and compares them against the recorded answers
+pub fn verify_day(day: u32, solver: &dyn Solver, input: &str, answers: &Answers) -> Vec<Check> {
+    let parsed = match solver.parse(input) {
+        Ok(parsed) => parsed,
+        Err(e) => return error_checks(day, &e.to_string()),
+    };
+
+    Part::ALL
+        .iter()
+        .map(|part| {
+            let actual = parsed.run(*part);
+            let outcome = match answers.get(&(day, *part)) {
+                Some(expected) if *expected == actual => Outcome::Pass,
+                Some(expected) => Outcome::Fail {
+                    expected: expected.clone(),
+                    actual,
+                },
+                None => Outcome::Missing { actual },
+            };
+
+            Check {
+                day,
+                part: *part,
+                outcome,
+            }
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::solution::registry;
+
+    #[test]
+    fn parse_answers_test() {
+        let answers = parse_answers("# day part answer\n1 1 24000\n\n5 2 MCD\n").unwrap();
+        assert_eq!(answers.len(), 2);
+        assert_eq!(answers[&(1, Part::One)], "24000");
+        assert_eq!(answers[&(5, Part::Two)], "MCD");
+
+        let error = parse_answers("1 1 24000\n1 3 45000").err().unwrap();
+        assert_eq!(error.line, 2);
+    }
+
+    #[test]
+    fn verify_day_test() {
+        let registry = registry();
+        let answers = parse_answers("1 1 4000\n1 2 1").unwrap();
+        let checks = verify_day(1, registry[&1].as_ref(), "1000\n2000\n\n4000", &answers);
+        assert_eq!(checks[0].outcome, Outcome::Pass);
+        assert_eq!(
+            checks[1].outcome,
+            Outcome::Fail {
+                expected: String::from("1"),
+                actual: String::from("7000"),
+            }
+        );
+
+        let checks = verify_day(2, registry[&2].as_ref(), "A Y", &answers);
+        assert_eq!(
+            checks[0].outcome,
+            Outcome::Missing {
+                actual: String::from("8"),
+            }
+        );
+    }
+}